@@ -181,6 +181,10 @@ fn get_config_path(shell: &str) -> Option<PathBuf> {
                 }
             }
             "zsh" => Some(".zshrc"),
+            "tcsh" => Some(".tcshrc"),
+            "nu" => Some(".config/nushell/config.nu"),
+            "elvish" => Some(".elvish/rc.elv"),
+            "xonsh" => Some(".xonshrc"),
             _ => None,
         }
         .map(|path| home_dir.join(path))
@@ -8,7 +8,11 @@ pub struct CharacterConfig<'a> {
     pub symbol: SegmentConfig<'a>,
     pub error_symbol: SegmentConfig<'a>,
     pub vicmd_symbol: SegmentConfig<'a>,
+    pub replace_symbol: SegmentConfig<'a>,
+    pub replace_one_symbol: SegmentConfig<'a>,
+    pub visual_symbol: SegmentConfig<'a>,
     pub use_symbol_for_status: bool,
+    pub use_pipestatus: bool,
     pub style_success: Style,
     pub style_failure: Style,
     pub disabled: bool,
@@ -20,7 +24,11 @@ impl<'a> RootModuleConfig<'a> for CharacterConfig<'a> {
             symbol: SegmentConfig::new("❯"),
             error_symbol: SegmentConfig::new("✖"),
             vicmd_symbol: SegmentConfig::new("❮"),
+            replace_symbol: SegmentConfig::new("❮").with_style(Some(Color::Purple.bold())),
+            replace_one_symbol: SegmentConfig::new("❮").with_style(Some(Color::Purple.bold())),
+            visual_symbol: SegmentConfig::new("❮").with_style(Some(Color::Yellow.bold())),
             use_symbol_for_status: false,
+            use_pipestatus: false,
             style_success: Color::Green.bold(),
             style_failure: Color::Red.bold(),
             disabled: false,
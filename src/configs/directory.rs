@@ -1,4 +1,4 @@
-use crate::config::{ModuleConfig, RootModuleConfig};
+use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
 use std::collections::HashMap;
 
 use ansi_term::{Color, Style};
@@ -8,9 +8,13 @@ use starship_module_config_derive::ModuleConfig;
 pub struct DirectoryConfig<'a> {
     pub truncation_length: i64,
     pub truncate_to_repo: bool,
+    pub truncate_to_superproject: bool,
+    pub worktree_symbol: SegmentConfig<'a>,
+    pub submodule_symbol: SegmentConfig<'a>,
     pub substitutions: HashMap<String, &'a str>,
     pub fish_style_pwd_dir_length: i64,
     pub use_logical_path: bool,
+    pub hyperlink: bool,
     pub prefix: &'a str,
     pub style: Style,
     pub disabled: bool,
@@ -21,9 +25,13 @@ impl<'a> RootModuleConfig<'a> for DirectoryConfig<'a> {
         DirectoryConfig {
             truncation_length: 3,
             truncate_to_repo: true,
+            truncate_to_superproject: false,
+            worktree_symbol: SegmentConfig::default(),
+            submodule_symbol: SegmentConfig::default(),
             fish_style_pwd_dir_length: 0,
             substitutions: HashMap::new(),
             use_logical_path: true,
+            hyperlink: false,
             prefix: "in ",
             style: Color::Cyan.bold(),
             disabled: false,
@@ -9,6 +9,19 @@ pub struct GitBranchConfig<'a> {
     pub truncation_length: i64,
     pub truncation_symbol: &'a str,
     pub branch_name: SegmentConfig<'a>,
+    pub remote_symbol: SegmentConfig<'a>,
+    pub remote_name: SegmentConfig<'a>,
+    pub remote_branch: SegmentConfig<'a>,
+    pub always_show_remote: bool,
+    pub no_upstream_symbol: SegmentConfig<'a>,
+    pub detached_tag: bool,
+    pub detached_describe: bool,
+    pub detached_hash_length: usize,
+    pub worktree_symbol: SegmentConfig<'a>,
+    pub worktree: SegmentConfig<'a>,
+    pub submodule_symbol: SegmentConfig<'a>,
+    pub superproject: SegmentConfig<'a>,
+    pub hyperlink: bool,
     pub style: Style,
     pub disabled: bool,
 }
@@ -20,6 +33,20 @@ impl<'a> RootModuleConfig<'a> for GitBranchConfig<'a> {
             truncation_length: std::i64::MAX,
             truncation_symbol: "…",
             branch_name: SegmentConfig::default(),
+            remote_symbol: SegmentConfig::new(":"),
+            remote_name: SegmentConfig::default(),
+            remote_branch: SegmentConfig::default(),
+            always_show_remote: false,
+            no_upstream_symbol: SegmentConfig::default(),
+            detached_tag: true,
+            detached_describe: true,
+            // be consistent with git by default, which has DEFAULT_ABBREV set to 7
+            detached_hash_length: 7,
+            worktree_symbol: SegmentConfig::new(""),
+            worktree: SegmentConfig::default(),
+            submodule_symbol: SegmentConfig::new(" in "),
+            superproject: SegmentConfig::default(),
+            hyperlink: false,
             style: Color::Purple.bold(),
             disabled: false,
         }
@@ -11,6 +11,13 @@ pub struct GitCommitConfig<'a> {
     pub suffix: &'a str,
     pub style: Style,
     pub only_detached: bool,
+    pub detached_tag: bool,
+    pub detached_describe: bool,
+    pub tag_symbol: SegmentConfig<'a>,
+    pub tag: SegmentConfig<'a>,
+    pub tag_disabled: bool,
+    pub tag_hide_detached_name: bool,
+    pub hyperlink: bool,
     pub disabled: bool,
 }
 
@@ -24,6 +31,13 @@ impl<'a> RootModuleConfig<'a> for GitCommitConfig<'a> {
             suffix: ") ",
             style: Color::Green.bold(),
             only_detached: true,
+            detached_tag: false,
+            detached_describe: false,
+            tag_symbol: SegmentConfig::new(" 🏷  "),
+            tag: SegmentConfig::default(),
+            tag_disabled: true,
+            tag_hide_detached_name: false,
+            hyperlink: false,
             disabled: false,
         }
     }
@@ -0,0 +1,44 @@
+use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+
+use ansi_term::{Color, Style};
+use starship_module_config_derive::ModuleConfig;
+
+#[derive(Clone, ModuleConfig)]
+pub struct GitLastCommitConfig<'a> {
+    pub age_suffix: SegmentConfig<'a>,
+    pub author_symbol: SegmentConfig<'a>,
+    pub show_author: bool,
+    pub uncommitted_display: Vec<UncommittedDisplayConfig>,
+    pub prefix: &'a str,
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl<'a> RootModuleConfig<'a> for GitLastCommitConfig<'a> {
+    fn new() -> Self {
+        GitLastCommitConfig {
+            age_suffix: SegmentConfig::new(" ago"),
+            author_symbol: SegmentConfig::new(" by "),
+            show_author: false,
+            uncommitted_display: vec![
+                UncommittedDisplayConfig {
+                    threshold: 3_600,
+                    style: Color::Yellow.bold(),
+                },
+                UncommittedDisplayConfig {
+                    threshold: 14_400,
+                    style: Color::Red.bold(),
+                },
+            ],
+            prefix: "committed ",
+            style: Color::Blue.bold(),
+            disabled: true,
+        }
+    }
+}
+
+#[derive(Clone, ModuleConfig)]
+pub struct UncommittedDisplayConfig {
+    pub threshold: i64,
+    pub style: Style,
+}
@@ -0,0 +1,29 @@
+use crate::config::{ModuleConfig, RootModuleConfig};
+
+use ansi_term::{Color, Style};
+use starship_module_config_derive::ModuleConfig;
+
+#[derive(Clone, ModuleConfig)]
+pub struct GitMetricsConfig<'a> {
+    pub added_symbol: &'a str,
+    pub deleted_symbol: &'a str,
+    pub added_style: Style,
+    pub deleted_style: Style,
+    pub include_staged: bool,
+    pub only_nonzero_diffs: bool,
+    pub disabled: bool,
+}
+
+impl<'a> RootModuleConfig<'a> for GitMetricsConfig<'a> {
+    fn new() -> Self {
+        GitMetricsConfig {
+            added_symbol: "+",
+            deleted_symbol: "-",
+            added_style: Color::Green.bold(),
+            deleted_style: Color::Red.bold(),
+            include_staged: true,
+            only_nonzero_diffs: true,
+            disabled: true,
+        }
+    }
+}
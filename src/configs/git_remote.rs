@@ -0,0 +1,32 @@
+use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+use std::collections::HashMap;
+
+use ansi_term::{Color, Style};
+use starship_module_config_derive::ModuleConfig;
+
+#[derive(Clone, ModuleConfig)]
+pub struct GitRemoteConfig<'a> {
+    pub symbols: HashMap<String, &'a str>,
+    pub host: SegmentConfig<'a>,
+    pub owner: SegmentConfig<'a>,
+    pub repo: SegmentConfig<'a>,
+    pub show_host: bool,
+    pub prefix: &'a str,
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl<'a> RootModuleConfig<'a> for GitRemoteConfig<'a> {
+    fn new() -> Self {
+        GitRemoteConfig {
+            symbols: HashMap::new(),
+            host: SegmentConfig::default(),
+            owner: SegmentConfig::default(),
+            repo: SegmentConfig::default(),
+            show_host: false,
+            prefix: "at ",
+            style: Color::Blue.bold(),
+            disabled: true,
+        }
+    }
+}
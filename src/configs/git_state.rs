@@ -13,6 +13,10 @@ pub struct GitStateConfig<'a> {
     pub am: SegmentConfig<'a>,
     pub am_or_rebase: SegmentConfig<'a>,
     pub progress_divider: SegmentConfig<'a>,
+    pub onto_symbol: SegmentConfig<'a>,
+    pub step_symbol: SegmentConfig<'a>,
+    pub show_step: bool,
+    pub conflicted_symbol: SegmentConfig<'a>,
     pub style: Style,
     pub disabled: bool,
 }
@@ -28,6 +32,10 @@ impl<'a> RootModuleConfig<'a> for GitStateConfig<'a> {
             am: SegmentConfig::new("AM"),
             am_or_rebase: SegmentConfig::new("AM/REBASE"),
             progress_divider: SegmentConfig::new("/"),
+            onto_symbol: SegmentConfig::new(" onto "),
+            step_symbol: SegmentConfig::new(": "),
+            show_step: false,
+            conflicted_symbol: SegmentConfig::new(" ="),
             style: Color::Yellow.bold(),
             disabled: false,
         }
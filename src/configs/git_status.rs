@@ -11,6 +11,11 @@ pub struct GitStatusConfig<'a> {
     pub behind: SegmentConfig<'a>,
     pub diverged: SegmentConfig<'a>,
     pub show_sync_count: bool,
+    pub compare_to: Vec<&'a str>,
+    pub base_ahead: SegmentConfig<'a>,
+    pub base_ahead_count: CountConfig,
+    pub base_behind: SegmentConfig<'a>,
+    pub base_behind_count: CountConfig,
     pub conflicted: SegmentConfig<'a>,
     pub conflicted_count: CountConfig,
     pub deleted: SegmentConfig<'a>,
@@ -23,6 +28,10 @@ pub struct GitStatusConfig<'a> {
     pub staged_count: CountConfig,
     pub untracked: SegmentConfig<'a>,
     pub untracked_count: CountConfig,
+    pub dirty: SegmentConfig<'a>,
+    pub ignore_submodules: bool,
+    pub large_repo_threshold: usize,
+    pub status_timeout: u64,
     pub prefix: &'a str,
     pub suffix: &'a str,
     pub style: Style,
@@ -39,6 +48,17 @@ impl<'a> RootModuleConfig<'a> for GitStatusConfig<'a> {
             diverged: SegmentConfig::new("⇕"),
             conflicted: SegmentConfig::new("="),
             show_sync_count: false,
+            compare_to: vec![],
+            base_ahead: SegmentConfig::new("↑"),
+            base_ahead_count: CountConfig {
+                enabled: true,
+                style: None,
+            },
+            base_behind: SegmentConfig::new("↓"),
+            base_behind_count: CountConfig {
+                enabled: true,
+                style: None,
+            },
             conflicted_count: CountConfig::default(),
             deleted: SegmentConfig::new("✘"),
             deleted_count: CountConfig::default(),
@@ -50,6 +70,10 @@ impl<'a> RootModuleConfig<'a> for GitStatusConfig<'a> {
             staged_count: CountConfig::default(),
             untracked: SegmentConfig::new("?"),
             untracked_count: CountConfig::default(),
+            dirty: SegmentConfig::new("*"),
+            ignore_submodules: false,
+            large_repo_threshold: 0,
+            status_timeout: 0,
             prefix: "[",
             suffix: "] ",
             style: Color::Red.bold(),
@@ -0,0 +1,30 @@
+use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+use std::collections::HashMap;
+
+use ansi_term::{Color, Style};
+use starship_module_config_derive::ModuleConfig;
+
+#[derive(Clone, ModuleConfig)]
+pub struct GitUserConfig<'a> {
+    pub aliases: HashMap<String, SegmentConfig<'a>>,
+    pub show_name: bool,
+    pub no_identity: SegmentConfig<'a>,
+    pub warning_style: Style,
+    pub prefix: &'a str,
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl<'a> RootModuleConfig<'a> for GitUserConfig<'a> {
+    fn new() -> Self {
+        GitUserConfig {
+            aliases: HashMap::new(),
+            show_name: false,
+            no_identity: SegmentConfig::new("no identity"),
+            warning_style: Color::Red.bold(),
+            prefix: "as ",
+            style: Color::Cyan.bold(),
+            disabled: true,
+        }
+    }
+}
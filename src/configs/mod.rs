@@ -14,8 +14,12 @@ pub mod env_var;
 pub mod erlang;
 pub mod git_branch;
 pub mod git_commit;
+pub mod git_last_commit;
+pub mod git_metrics;
+pub mod git_remote;
 pub mod git_state;
 pub mod git_status;
+pub mod git_user;
 pub mod go;
 pub mod haskell;
 pub mod hg_branch;
@@ -37,8 +41,10 @@ pub mod ruby;
 pub mod rust;
 pub mod singularity;
 mod starship_root;
+pub mod status;
 pub mod terraform;
 pub mod time;
+pub mod title;
 pub mod username;
 pub mod zig;
 
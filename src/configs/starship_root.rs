@@ -1,4 +1,4 @@
-use crate::config::{ModuleConfig, RootModuleConfig};
+use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
 
 use starship_module_config_derive::ModuleConfig;
 
@@ -7,6 +7,13 @@ pub struct StarshipRootConfig<'a> {
     pub add_newline: bool,
     pub prompt_order: Vec<&'a str>,
     pub scan_timeout: u64,
+    pub scan_parents: bool,
+    pub scan_depth: usize,
+    pub async_render: bool,
+    pub async_modules: Vec<&'a str>,
+    pub async_threshold: u64,
+    pub async_placeholder: SegmentConfig<'a>,
+    pub shell_integration: bool,
 }
 
 impl<'a> RootModuleConfig<'a> for StarshipRootConfig<'a> {
@@ -23,9 +30,13 @@ impl<'a> RootModuleConfig<'a> for StarshipRootConfig<'a> {
                 "kubernetes",
                 "directory",
                 "git_branch",
+                "git_remote",
                 "git_commit",
+                "git_last_commit",
                 "git_state",
                 "git_status",
+                "git_metrics",
+                "git_user",
                 "hg_branch",
                 "docker_context",
                 "package",
@@ -63,9 +74,18 @@ impl<'a> RootModuleConfig<'a> for StarshipRootConfig<'a> {
                 #[cfg(feature = "battery")]
                 "battery",
                 "time",
+                "status",
                 "character",
             ],
             scan_timeout: 30,
+            scan_parents: false,
+            scan_depth: 0,
+            async_render: false,
+            // Modules which are known to be slow in large repositories
+            async_modules: vec!["git_status"],
+            async_threshold: 100,
+            async_placeholder: SegmentConfig::new("…"),
+            shell_integration: false,
         }
     }
 }
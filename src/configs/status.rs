@@ -0,0 +1,37 @@
+use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+
+use ansi_term::{Color, Style};
+use starship_module_config_derive::ModuleConfig;
+
+#[derive(Clone, ModuleConfig)]
+pub struct StatusConfig<'a> {
+    pub symbol: SegmentConfig<'a>,
+    pub success_symbol: SegmentConfig<'a>,
+    pub not_executable_symbol: SegmentConfig<'a>,
+    pub not_found_symbol: SegmentConfig<'a>,
+    pub signal_symbol: SegmentConfig<'a>,
+    pub show_meaning: bool,
+    pub pipestatus: bool,
+    pub pipestatus_separator: &'a str,
+    pub show_on_success: bool,
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl<'a> RootModuleConfig<'a> for StatusConfig<'a> {
+    fn new() -> Self {
+        StatusConfig {
+            symbol: SegmentConfig::new("✖"),
+            success_symbol: SegmentConfig::new("✔").with_style(Some(Color::Green.bold())),
+            not_executable_symbol: SegmentConfig::new("🚫"),
+            not_found_symbol: SegmentConfig::new("🔍"),
+            signal_symbol: SegmentConfig::new("⚡"),
+            show_meaning: true,
+            pipestatus: true,
+            pipestatus_separator: "|",
+            show_on_success: false,
+            style: Color::Red.bold(),
+            disabled: true,
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use crate::config::{ModuleConfig, RootModuleConfig};
+
+use starship_module_config_derive::ModuleConfig;
+
+#[derive(Clone, ModuleConfig)]
+pub struct TitleConfig<'a> {
+    pub format: &'a str,
+    pub preexec_format: &'a str,
+    pub disabled: bool,
+}
+
+impl<'a> RootModuleConfig<'a> for TitleConfig<'a> {
+    fn new() -> Self {
+        TitleConfig {
+            format: "$directory",
+            preexec_format: "$command",
+            disabled: true,
+        }
+    }
+}
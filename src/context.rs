@@ -1,16 +1,25 @@
-use crate::config::StarshipConfig;
+use crate::config::{RootModuleConfig, StarshipConfig};
+use crate::configs::git_status::GitStatusConfig;
 use crate::module::Module;
 
 use crate::modules;
+use crate::utils;
 use clap::ArgMatches;
-use git2::{ErrorCode::UnbornBranch, Repository, RepositoryState};
+use git2::{
+    DescribeFormatOptions, DescribeOptions, ErrorCode::UnbornBranch, ObjectType, Reference,
+    Repository, RepositoryState, Status,
+};
 use once_cell::sync::OnceCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::string::String;
-use std::time::{Duration, SystemTime};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use sysinfo::{ProcessExt, RefreshKind, System, SystemExt};
 
 /// Context contains data or common methods that may be used by multiple modules.
 /// The data contained within Context will be relevant to this particular rendering
@@ -25,9 +34,17 @@ pub struct Context<'a> {
     /// A struct containing directory contents in a lookup-optimised format.
     dir_contents: OnceCell<DirContents>,
 
+    /// The contents of each parent directory, up to the root of the repository.
+    parent_dir_contents: OnceCell<Vec<DirContents>>,
+
     /// Properties to provide to modules.
     pub properties: HashMap<&'a str, String>,
 
+    /// Whether the shell expands parameters and commands in the prompt, which
+    /// bash's `promptvars` and zsh's `prompt_subst` options control. The init
+    /// scripts pass `--no-promptvars` when it is off.
+    pub promptvars: bool,
+
     /// Private field to store Git information for modules who need it
     repo: OnceCell<Repo>,
 
@@ -69,17 +86,19 @@ impl<'a> Context<'a> {
             .filter(|(_, v)| !v.vals.is_empty())
             .map(|(a, b)| (*a, b.vals.first().cloned().unwrap().into_string().unwrap()))
             .collect();
-
         // TODO: Currently gets the physical directory. Get the logical directory.
         let current_dir = Context::expand_tilde(dir.into());
 
-        let shell = Context::get_shell();
+        let shell = Context::get_shell(&arguments);
+        let promptvars = !arguments.is_present("no_promptvars");
 
         Context {
             config,
             properties,
+            promptvars,
             current_dir,
             dir_contents: OnceCell::new(),
+            parent_dir_contents: OnceCell::new(),
             repo: OnceCell::new(),
             shell,
         }
@@ -94,6 +113,16 @@ impl<'a> Context<'a> {
         dir
     }
 
+    /// The exit codes of the commands of the last pipeline, from `--pipestatus`,
+    /// or `None` if the shell didn't give them or one isn't a number
+    pub fn pipestatus(&self) -> Option<Vec<i32>> {
+        let pipestatus = self.properties.get("pipestatus")?;
+        pipestatus
+            .split_whitespace()
+            .map(|code| code.parse().ok())
+            .collect()
+    }
+
     /// Create a new module
     pub fn new_module(&self, name: &str) -> Module {
         let config = self.config.get_module_config(name);
@@ -124,8 +153,15 @@ impl<'a> Context<'a> {
     // returns a new ScanDir struct with reference to current dir_files of context
     // see ScanDir for methods
     pub fn try_begin_scan(&'a self) -> Option<ScanDir<'a>> {
+        let parents = if self.config.get_root_config().scan_parents {
+            self.parent_dir_contents()
+        } else {
+            &[]
+        };
+
         Some(ScanDir {
             dir_contents: self.dir_contents().ok()?,
+            parents,
             files: &[],
             folders: &[],
             extensions: &[],
@@ -136,6 +172,7 @@ impl<'a> Context<'a> {
     pub fn get_repo(&self) -> Result<&Repo, std::io::Error> {
         self.repo
             .get_or_try_init(|| -> Result<Repo, std::io::Error> {
+                let start = Instant::now();
                 let repository = Repository::discover(&self.current_dir).ok();
                 let branch = repository
                     .as_ref()
@@ -144,33 +181,122 @@ impl<'a> Context<'a> {
                     .as_ref()
                     .and_then(|repo| repo.workdir().map(Path::to_path_buf));
                 let state = repository.as_ref().map(|repo| repo.state());
+                let worktree = repository.as_ref().and_then(get_worktree_name);
+                utils::trace_resource("get_repo", start.elapsed());
+
+                // Every module computing the status has to use the same settings
+                let status_config =
+                    GitStatusConfig::try_load(self.config.get_module_config("git_status"));
 
                 Ok(Repo {
                     branch,
                     root,
                     state,
+                    worktree,
+                    superproject: OnceCell::new(),
+                    repository: repository.map(Mutex::new),
+                    settings: StatusSettings::from(&status_config),
+                    worktree_scan: OnceCell::new(),
+                    ahead_behind: OnceCell::new(),
+                    upstream: OnceCell::new(),
+                    head_tags: OnceCell::new(),
                 })
             })
     }
 
     pub fn dir_contents(&self) -> Result<&DirContents, std::io::Error> {
         self.dir_contents.get_or_try_init(|| {
+            let start = Instant::now();
+            let root_config = self.config.get_root_config();
+            let timeout = Duration::from_millis(root_config.scan_timeout);
+            // Only needed to honour `.gitignore` when scanning subdirectories
+            let repository = match root_config.scan_depth {
+                0 => None,
+                _ => self.get_repo().ok().and_then(Repo::repository),
+            };
+            let dir_contents = DirContents::from_path_with_timeout(
+                &self.current_dir,
+                timeout,
+                root_config.scan_depth,
+                repository.as_deref(),
+            );
+            utils::trace_resource("dir_contents", start.elapsed());
+            dir_contents
+        })
+    }
+
+    /// Will lazily list the parent directories of `current_dir`, up to the root
+    /// of the repository it is in, when `scan_parents` is enabled. Outside of a
+    /// repository, they are listed up to the home directory, leaving it out.
+    fn parent_dir_contents(&self) -> &[DirContents] {
+        self.parent_dir_contents.get_or_init(|| {
+            let repo_root = self.get_repo().ok().and_then(|repo| repo.root.as_ref());
+            let home = dirs::home_dir();
+            let is_project_dir = |dir: &Path| match (repo_root, &home) {
+                (Some(root), _) => dir.starts_with(root),
+                (None, Some(home)) => dir.starts_with(home) && dir != home,
+                (None, None) => false,
+            };
+
+            let start = Instant::now();
             let timeout = Duration::from_millis(self.config.get_root_config().scan_timeout);
-            DirContents::from_path_with_timeout(&self.current_dir, timeout)
+            let parents = self
+                .current_dir
+                .ancestors()
+                .skip(1)
+                .take_while(|dir| is_project_dir(dir))
+                .filter_map(|dir| DirContents::from_path_with_timeout(dir, timeout, 0, None).ok())
+                .collect();
+            utils::trace_resource("parent_dir_contents", start.elapsed());
+            parents
         })
     }
 
-    fn get_shell() -> Shell {
-        let shell = std::env::var("STARSHIP_SHELL").unwrap_or_default();
-        match shell.as_str() {
+    /// The shell from `--shell`, then `$STARSHIP_SHELL`, which the init scripts
+    /// set. Failing those, starship was probably run by the shell itself, so
+    /// its parent process is looked at.
+    fn get_shell(arguments: &ArgMatches) -> Shell {
+        let shell = arguments
+            .value_of("shell")
+            .map(String::from)
+            .or_else(|| env::var("STARSHIP_SHELL").ok())
+            .filter(|shell| !shell.is_empty());
+
+        match shell {
+            Some(shell) => Context::shell_from_name(&shell),
+            None => Context::get_parent_process_name()
+                .map_or(Shell::Unknown, |name| Context::shell_from_name(&name)),
+        }
+    }
+
+    fn shell_from_name(name: &str) -> Shell {
+        // Login shells are started with a leading `-`
+        let name = name.trim_start_matches('-').trim_end_matches(".exe");
+        match name {
             "bash" => Shell::Bash,
             "fish" => Shell::Fish,
             "ion" => Shell::Ion,
-            "powershell" => Shell::PowerShell,
+            "powershell" | "pwsh" => Shell::PowerShell,
             "zsh" => Shell::Zsh,
+            "tcsh" => Shell::Tcsh,
+            "nu" => Shell::Nu,
+            "elvish" => Shell::Elvish,
+            "xonsh" => Shell::Xonsh,
             _ => Shell::Unknown,
         }
     }
+
+    fn get_parent_process_name() -> Option<String> {
+        let mut system = System::new_with_specifics(RefreshKind::new());
+        let pid = sysinfo::get_current_pid().ok()?;
+        system.refresh_process(pid);
+        let parent_pid = system.get_process(pid)?.parent()?;
+        system.refresh_process(parent_pid);
+        let name = system.get_process(parent_pid)?.name().to_string();
+
+        log::debug!("Detected the shell from the parent process: {}", name);
+        Some(name)
+    }
 }
 
 #[derive(Debug)]
@@ -187,11 +313,18 @@ pub struct DirContents {
 
 impl DirContents {
     #[cfg(test)]
-    fn from_path(base: &PathBuf) -> Result<Self, std::io::Error> {
-        Self::from_path_with_timeout(base, Duration::from_secs(30))
+    fn from_path(base: &Path) -> Result<Self, std::io::Error> {
+        Self::from_path_with_timeout(base, Duration::from_secs(30), 0, None)
     }
 
-    fn from_path_with_timeout(base: &PathBuf, timeout: Duration) -> Result<Self, std::io::Error> {
+    /// List the files in `base`, and in its subdirectories up to `depth` levels
+    /// below it. Subdirectories and files ignored by `repository` are skipped.
+    fn from_path_with_timeout(
+        base: &Path,
+        timeout: Duration,
+        depth: usize,
+        repository: Option<&Repository>,
+    ) -> Result<Self, std::io::Error> {
         let start = SystemTime::now();
 
         let mut folders: HashSet<PathBuf> = HashSet::new();
@@ -199,24 +332,41 @@ impl DirContents {
         let mut file_names: HashSet<String> = HashSet::new();
         let mut extensions: HashSet<String> = HashSet::new();
 
-        fs::read_dir(base)?
-            .take_while(|_| SystemTime::now().duration_since(start).unwrap() < timeout)
-            .filter_map(Result::ok)
-            .for_each(|entry| {
-                let path = PathBuf::from(entry.path().strip_prefix(base).unwrap());
-                if entry.path().is_dir() {
-                    folders.insert(path);
-                } else {
-                    if !path.to_string_lossy().starts_with('.') {
-                        path.extension()
-                            .map(|ext| extensions.insert(ext.to_string_lossy().to_string()));
-                    }
-                    if let Some(file_name) = path.file_name() {
-                        file_names.insert(file_name.to_string_lossy().to_string());
+        // Scan breadth-first, so the closest files are found before the timeout
+        let mut pending = VecDeque::new();
+        pending.push_back((base.to_path_buf(), 0));
+
+        while let Some((dir, level)) = pending.pop_front() {
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(err) if level == 0 => return Err(err),
+                Err(_) => continue,
+            };
+
+            entries
+                .take_while(|_| SystemTime::now().duration_since(start).unwrap() < timeout)
+                .filter_map(Result::ok)
+                // Ignored entries are listed in `base` itself, but never scanned
+                .filter(|entry| level == 0 || !is_ignored(repository, &entry.path()))
+                .for_each(|entry| {
+                    let path = PathBuf::from(entry.path().strip_prefix(base).unwrap());
+                    if entry.path().is_dir() {
+                        let is_scanned = level > 0 || !is_ignored(repository, &entry.path());
+                        if level < depth && entry.file_name() != ".git" && is_scanned {
+                            pending.push_back((entry.path(), level + 1));
+                        }
+                        folders.insert(path);
+                    } else {
+                        let file_name = entry.file_name().to_string_lossy().to_string();
+                        if !file_name.starts_with('.') {
+                            path.extension()
+                                .map(|ext| extensions.insert(ext.to_string_lossy().to_string()));
+                        }
+                        file_names.insert(file_name);
+                        files.insert(path);
                     }
-                    files.insert(path);
-                }
-            });
+                });
+        }
 
         log::trace!(
             "Building HashSets of directory files, folders and extensions took {:?}",
@@ -275,12 +425,267 @@ pub struct Repo {
 
     /// State
     pub state: Option<RepositoryState>,
+
+    /// If the repository is a linked worktree, this is the name of the worktree.
+    pub worktree: Option<String>,
+
+    /// If the repository is a submodule, this is the path to the root of the
+    /// repository containing it.
+    superproject: OnceCell<Option<PathBuf>>,
+
+    /// The repository, opened once and shared by the modules. `git2::Repository`
+    /// isn't `Sync`, so each module locks it while using it.
+    repository: Option<Mutex<Repository>>,
+
+    /// The options of `git_status` used to compute the status
+    settings: StatusSettings,
+
+    /// The status of the working tree, computed by the first module asking
+    worktree_scan: OnceCell<Option<WorktreeScan>>,
+
+    /// How far ahead and behind its upstream the current branch is
+    ahead_behind: OnceCell<Option<(usize, usize)>>,
+
+    /// The branch the current branch tracks
+    upstream: OnceCell<Option<Upstream>>,
+
+    /// The tags pointing at HEAD
+    head_tags: OnceCell<Vec<String>>,
+}
+
+impl Repo {
+    /// Locks the repository `current_dir` is within, for the caller's own use.
+    /// Results worth sharing between modules are cached by the other methods,
+    /// which lock it too, so the lock has to be released before calling them.
+    pub fn repository(&self) -> Option<MutexGuard<'_, Repository>> {
+        // A module panicking while holding the lock doesn't leave it unusable
+        let repository = self.repository.as_ref()?;
+        Some(repository.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Will lazily walk the repository status the first time a module requests it.
+    pub fn status(&self) -> Option<&RepoStatus> {
+        self.scan_worktree().map(|scan| &scan.status)
+    }
+
+    /// Will lazily compare the current branch with its upstream the first time
+    /// a module requests it.
+    pub fn ahead_behind(&self) -> Option<(usize, usize)> {
+        *self.ahead_behind.get_or_init(|| {
+            let start = Instant::now();
+            let repository = self.repository()?;
+            let ahead_behind = get_ahead_behind(&repository, self.branch.as_ref()?);
+            utils::trace_resource("ahead_behind", start.elapsed());
+
+            ahead_behind
+                .map_err(|err| log::trace!("No ahead/behind found: {}", err))
+                .ok()
+        })
+    }
+
+    /// Will lazily look up the repository containing this one, if it is a
+    /// submodule, the first time a module requests it.
+    pub fn superproject(&self) -> Option<&Path> {
+        self.superproject
+            .get_or_init(|| match (&self.worktree, &self.root) {
+                (None, Some(root)) => get_superproject(root),
+                _ => None,
+            })
+            .as_deref()
+    }
+
+    /// Will lazily look up the upstream of the current branch the first time a
+    /// module requests it.
+    pub fn upstream(&self) -> Option<&Upstream> {
+        self.upstream
+            .get_or_init(|| {
+                let repository = self.repository()?;
+                get_upstream(&repository, self.branch.as_ref()?)
+            })
+            .as_ref()
+    }
+
+    /// Will lazily look up the names of the tags pointing at HEAD, including
+    /// annotated tags, the first time a module requests them.
+    pub fn head_tags(&self) -> &[String] {
+        self.head_tags.get_or_init(|| {
+            let start = Instant::now();
+            let tags = self
+                .repository()
+                .and_then(|repository| get_head_tags(&repository));
+            utils::trace_resource("head_tags", start.elapsed());
+            tags.unwrap_or_default()
+        })
+    }
+
+    /// Compares HEAD with the first of `bases` which exists, e.g. `origin/main`.
+    /// Returns `None` if that base is the upstream, which `ahead_behind` compares.
+    pub fn ahead_behind_base(&self, bases: &[&str]) -> Option<(usize, usize)> {
+        if bases.is_empty() {
+            return None;
+        }
+
+        let upstream = self
+            .upstream()
+            .map(|upstream| format!("{}/{}", upstream.remote_name, upstream.branch));
+        let repository = self.repository()?;
+
+        let (base_name, base) = bases.iter().find_map(|base_name| {
+            let base = repository.revparse_single(base_name).ok()?;
+            Some((base_name, base.peel_to_commit().ok()?))
+        })?;
+        if upstream.as_deref() == Some(base_name) {
+            return None;
+        }
+
+        let head = repository.head().ok()?.peel_to_commit().ok()?;
+        repository
+            .graph_ahead_behind(head.id(), base.id())
+            .map_err(|err| log::debug!("Unable to compare with {}: {}", base_name, err))
+            .ok()
+    }
+
+    /// Counts the lines added and deleted in the working tree, compared with HEAD
+    /// if `include_staged` is set, or with the index otherwise.
+    ///
+    /// Like the status, the diff is skipped in repositories with more files than
+    /// `large_repo_threshold`, or when it takes longer than `status_timeout`.
+    pub fn diff_stats(&self, include_staged: bool) -> Option<(usize, usize)> {
+        let diff_stats = self.scan_worktree()?.diff_stats?;
+        if include_staged {
+            Some(diff_stats.with_head)
+        } else {
+            Some(diff_stats.with_index)
+        }
+    }
+
+    /// Gets the latest modification time of the files which differ from HEAD,
+    /// including untracked files, within the same budget as the status.
+    pub fn last_modified(&self) -> Option<SystemTime> {
+        self.scan_worktree()?.last_modified
+    }
+
+    /// Will lazily scan the working tree once, for the status, the diff and the
+    /// modification times together, the first time a module requests any of them.
+    fn scan_worktree(&self) -> Option<&WorktreeScan> {
+        self.worktree_scan
+            .get_or_init(|| {
+                let start = Instant::now();
+                let scan = scan_worktree(self.repository.as_ref()?, self.settings);
+                utils::trace_resource("repo_status", start.elapsed());
+
+                scan.map_err(|err| log::debug!("Unable to get repo status: {}", err))
+                    .ok()
+            })
+            .as_ref()
+    }
+
+    /// Names a detached HEAD by the tag pointing at it, then by `git describe`
+    /// relative to the closest tag, then by its abbreviated commit hash.
+    /// Returns `None` if HEAD isn't detached.
+    pub fn detached_head_name(
+        &self,
+        use_tag: bool,
+        use_describe: bool,
+        hash_length: usize,
+    ) -> Option<String> {
+        if !self.repository()?.head_detached().ok()? {
+            return None;
+        }
+
+        // `git describe` would also name HEAD by a tag pointing at it
+        let tag = match use_tag || use_describe {
+            true => self.head_tags().first().cloned(),
+            false => None,
+        };
+        let repository = self.repository()?;
+        let description = || {
+            let description = repository
+                .describe(
+                    DescribeOptions::new()
+                        .describe_tags()
+                        .max_candidates_tags(10),
+                )
+                .ok()?;
+            description
+                .format(Some(
+                    DescribeFormatOptions::new().abbreviated_size(hash_length as u32),
+                ))
+                .ok()
+        };
+
+        tag.or_else(|| if use_describe { description() } else { None })
+            .or_else(|| {
+                let head = repository.head().ok()?.peel_to_commit().ok()?;
+                Some(head.id().to_string().chars().take(hash_length).collect())
+            })
+    }
+}
+
+/// The branch a local branch tracks, from its `branch.<name>.remote` and
+/// `branch.<name>.merge` config
+#[derive(Debug, Clone, PartialEq)]
+pub struct Upstream {
+    /// The name of the remote, e.g. `origin`, or `.` for a local branch
+    pub remote_name: String,
+    /// The name of the branch on the remote, e.g. `main`
+    pub branch: String,
+}
+
+/// The number of files in various git states (staged, modified, deleted, etc...)
+#[derive(Default, Debug, Copy, Clone)]
+pub struct RepoStatus {
+    pub conflicted: usize,
+    pub deleted: usize,
+    pub renamed: usize,
+    pub modified: usize,
+    pub staged: usize,
+    pub untracked: usize,
+    pub stashed: usize,
+    /// The working tree was too large to scan, so only the index was compared
+    /// with HEAD, and every change is counted as `staged`
+    pub truncated: bool,
+}
+
+/// Everything found by scanning the working tree. The diff and the modification
+/// times are left out of a truncated scan.
+#[derive(Debug, Default)]
+struct WorktreeScan {
+    status: RepoStatus,
+    diff_stats: Option<DiffStats>,
+    last_modified: Option<SystemTime>,
+}
+
+/// The number of lines added and deleted in the working tree
+#[derive(Debug, Default, Clone, Copy)]
+struct DiffStats {
+    with_head: (usize, usize),
+    with_index: (usize, usize),
+}
+
+/// Options from the `git_status` config which change how the status is computed
+#[derive(Debug, Clone, Copy)]
+struct StatusSettings {
+    ignore_submodules: bool,
+    large_repo_threshold: usize,
+    status_timeout: Duration,
+}
+
+impl From<&GitStatusConfig<'_>> for StatusSettings {
+    fn from(config: &GitStatusConfig) -> Self {
+        StatusSettings {
+            ignore_submodules: config.ignore_submodules,
+            large_repo_threshold: config.large_repo_threshold,
+            status_timeout: Duration::from_millis(config.status_timeout),
+        }
+    }
 }
 
 // A struct of Criteria which will be used to verify current PathBuf is
 // of X language, criteria can be set via the builder pattern
 pub struct ScanDir<'a> {
     dir_contents: &'a DirContents,
+    parents: &'a [DirContents],
     files: &'a [&'a str],
     folders: &'a [&'a str],
     extensions: &'a [&'a str],
@@ -303,22 +708,39 @@ impl<'a> ScanDir<'a> {
     }
 
     /// based on the current Pathbuf check to see
-    /// if any of this criteria match or exist and returning a boolean
+    /// if any of this criteria match or exist and returning a boolean.
+    /// Parent directories are only checked for files, as extensions and
+    /// folders are too common to mark a project on their own.
     pub fn is_match(&self) -> bool {
         self.dir_contents.has_any_extension(self.extensions)
             || self.dir_contents.has_any_folder(self.folders)
             || self.dir_contents.has_any_file_name(self.files)
+            || self
+                .parents
+                .iter()
+                .any(|parent| parent.has_any_file_name(self.files))
     }
 }
 
+fn is_ignored(repository: Option<&Repository>, path: &Path) -> bool {
+    repository
+        .and_then(|repository| {
+            let path = path.strip_prefix(repository.workdir()?).ok()?;
+            repository.is_path_ignored(path).ok()
+        })
+        .unwrap_or(false)
+}
+
 fn get_current_branch(repository: &Repository) -> Option<String> {
     let head = match repository.head() {
         Ok(reference) => reference,
         Err(e) => {
             return if e.code() == UnbornBranch {
                 // HEAD should only be an unborn branch if the repository is fresh,
-                // in that case assume "master"
-                Some(String::from("master"))
+                // in that case the branch is the one HEAD points to
+                let head = repository.find_reference("HEAD").ok()?;
+                let target = head.symbolic_target()?;
+                Some(target.trim_start_matches("refs/heads/").to_string())
             } else {
                 None
             };
@@ -330,6 +752,488 @@ fn get_current_branch(repository: &Repository) -> Option<String> {
     shorthand.map(std::string::ToString::to_string)
 }
 
+/// Gets the name of a linked worktree, whose git directory is
+/// `.git/worktrees/<name>` in the main worktree
+fn get_worktree_name(repository: &Repository) -> Option<String> {
+    if !repository.is_worktree() {
+        return None;
+    }
+
+    let name = repository.path().file_name()?;
+    name.to_str().map(String::from)
+}
+
+/// Gets the root of the repository containing a submodule. Like linked
+/// worktrees, submodules have a `.git` file rather than a directory, pointing
+/// into the `.git/modules` of their superproject.
+fn get_superproject(root: &Path) -> Option<PathBuf> {
+    if !root.join(".git").is_file() {
+        return None;
+    }
+
+    let superproject = Repository::discover(root.parent()?).ok()?;
+    let superproject_root = superproject.workdir()?;
+    let is_submodule = superproject
+        .submodules()
+        .ok()?
+        .iter()
+        .any(|submodule| superproject_root.join(submodule.path()) == root);
+
+    if is_submodule {
+        Some(superproject_root.to_path_buf())
+    } else {
+        None
+    }
+}
+
+/// Gets the number of lines added and deleted by the diff of the working tree
+/// with HEAD, and with the index. Only the `changed` paths found by the status
+/// walk are diffed.
+fn get_diff_stats(
+    repository: &Repository,
+    settings: StatusSettings,
+    changed: &[String],
+) -> Result<DiffStats, git2::Error> {
+    if changed.is_empty() {
+        return Ok(DiffStats::default());
+    }
+
+    let mut diff_options = git2::DiffOptions::new();
+    diff_options
+        .ignore_submodules(settings.ignore_submodules)
+        .disable_pathspec_match(true);
+    for path in changed {
+        diff_options.pathspec(path);
+    }
+
+    let head_tree = repository
+        .head()
+        .ok()
+        .and_then(|head| head.peel_to_tree().ok());
+    let with_head =
+        repository.diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut diff_options))?;
+    let with_index = repository.diff_index_to_workdir(None, Some(&mut diff_options))?;
+
+    let lines = |diff: git2::Diff| -> Result<(usize, usize), git2::Error> {
+        let stats = diff.stats()?;
+        Ok((stats.insertions(), stats.deletions()))
+    };
+    Ok(DiffStats {
+        with_head: lines(with_head)?,
+        with_index: lines(with_index)?,
+    })
+}
+
+/// Gets the latest modification time of the `changed` paths found by the status
+/// walk. Untracked directories are reported as a whole, so the files within them
+/// are looked at.
+fn get_last_modified(repository: &Repository, changed: &[String]) -> Option<SystemTime> {
+    let workdir = repository.workdir()?;
+
+    fn latest_modified(path: &Path) -> Option<SystemTime> {
+        let metadata = fs::symlink_metadata(path).ok()?;
+        if !metadata.is_dir() {
+            return metadata.modified().ok();
+        }
+        fs::read_dir(path)
+            .ok()?
+            .filter_map(Result::ok)
+            .filter_map(|entry| latest_modified(&entry.path()))
+            .max()
+    }
+
+    changed
+        .iter()
+        .filter_map(|path| latest_modified(&workdir.join(path)))
+        .max()
+}
+
+/// Scans the working tree of the repository at `path`.
+///
+/// Repositories with more files in the index than `large_repo_threshold`, or
+/// whose scan takes longer than `status_timeout`, only have their index
+/// compared with HEAD, which doesn't need to look at the working tree.
+fn scan_worktree(
+    repository: &Mutex<Repository>,
+    settings: StatusSettings,
+) -> Result<WorktreeScan, git2::Error> {
+    let lock = || repository.lock().unwrap_or_else(PoisonError::into_inner);
+    let truncated_scan = || {
+        get_index_status(&mut lock(), settings).map(|status| WorktreeScan {
+            status,
+            ..WorktreeScan::default()
+        })
+    };
+
+    let (file_count, path) = {
+        let repository = lock();
+        (repository.index()?.len(), repository.path().to_path_buf())
+    };
+    if settings.large_repo_threshold > 0 && file_count > settings.large_repo_threshold {
+        log::debug!(
+            "Repo has {} files, more than large_repo_threshold, only checking the index",
+            file_count
+        );
+        return truncated_scan();
+    }
+
+    if settings.status_timeout == Duration::from_millis(0) {
+        return walk_worktree(&mut lock(), settings, &AtomicBool::new(false));
+    }
+
+    // The walk may outlive the timeout, so rather than holding the lock that long,
+    // the thread opens the repository again
+    let (sender, receiver) = mpsc::channel();
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let trace = utils::current_trace();
+    let thread_cancelled = Arc::clone(&cancelled);
+    thread::spawn(move || {
+        let scan = utils::in_trace(trace, || {
+            Repository::open(path).and_then(|mut repository| {
+                walk_worktree(&mut repository, settings, &thread_cancelled)
+            })
+        });
+        sender.send(scan).ok();
+    });
+
+    match receiver.recv_timeout(settings.status_timeout) {
+        Ok(scan) => scan,
+        Err(_) => {
+            // libgit2 can't interrupt the walk itself, so the thread only stops
+            // before its next step, if starship hasn't exited by then
+            cancelled.store(true, Ordering::Relaxed);
+            log::debug!("Repo status took longer than status_timeout, only checking the index");
+            truncated_scan()
+        }
+    }
+}
+
+/// Walks the working tree for the status of every file, then diffs it and
+/// looks at when it was modified, unless `cancelled` is set in between
+fn walk_worktree(
+    repository: &mut Repository,
+    settings: StatusSettings,
+    cancelled: &AtomicBool,
+) -> Result<WorktreeScan, git2::Error> {
+    let (statuses, changed) = get_statuses(repository, settings)?;
+    let statuses_count = count_statuses(statuses);
+
+    let status = RepoStatus {
+        conflicted: *statuses_count.get("conflicted").unwrap_or(&0),
+        deleted: *statuses_count.get("deleted").unwrap_or(&0),
+        renamed: *statuses_count.get("renamed").unwrap_or(&0),
+        modified: *statuses_count.get("modified").unwrap_or(&0),
+        staged: *statuses_count.get("staged").unwrap_or(&0),
+        untracked: *statuses_count.get("untracked").unwrap_or(&0),
+        stashed: stashed_count(repository)?,
+        truncated: false,
+    };
+    let mut scan = WorktreeScan {
+        status,
+        ..WorktreeScan::default()
+    };
+
+    if cancelled.load(Ordering::Relaxed) {
+        return Ok(scan);
+    }
+    scan.diff_stats = get_diff_stats(repository, settings, &changed)
+        .map_err(|err| log::debug!("Unable to get diff stats: {}", err))
+        .ok();
+
+    if cancelled.load(Ordering::Relaxed) {
+        return Ok(scan);
+    }
+    scan.last_modified = get_last_modified(repository, &changed);
+
+    Ok(scan)
+}
+
+/// Gets the status of every file in the repository, and the paths of the files
+/// which changed, including the original paths of renamed files.
+///
+/// libgit2 supports neither the untracked cache nor fsmonitor, so repositories
+/// which enabled either of them are asked through `git status` instead.
+fn get_statuses(
+    repository: &mut Repository,
+    settings: StatusSettings,
+) -> Result<(Vec<Status>, Vec<String>), git2::Error> {
+    let config = repository.config()?;
+    let untracked_cache = config.get_bool("core.untrackedCache").unwrap_or(false);
+    let fsmonitor = config
+        .get_string("core.fsmonitor")
+        .map(|fsmonitor| !fsmonitor.is_empty() && fsmonitor != "false")
+        .unwrap_or(false);
+
+    if untracked_cache || fsmonitor {
+        if let Some(statuses) = repository
+            .workdir()
+            .and_then(|workdir| get_git_cli_statuses(workdir, settings))
+        {
+            return Ok(statuses);
+        }
+        log::debug!("Unable to get status from git, falling back to libgit2");
+    }
+
+    let mut status_options = git2::StatusOptions::new();
+
+    match config.get_entry("status.showUntrackedFiles") {
+        Ok(entry) => status_options.include_untracked(entry.value() != Some("no")),
+        _ => status_options.include_untracked(true),
+    };
+    status_options
+        .renames_from_rewrites(true)
+        .renames_head_to_index(true)
+        .renames_index_to_workdir(true)
+        .exclude_submodules(settings.ignore_submodules)
+        .include_unmodified(true);
+
+    let entries = repository.statuses(Some(&mut status_options))?;
+    let statuses: Vec<Status> = entries.iter().map(|s| s.status()).collect();
+
+    if statuses.is_empty() {
+        return Err(git2::Error::from_str("Repo has no status"));
+    }
+
+    let mut changed: Vec<String> = entries
+        .iter()
+        .filter(|entry| !entry.status().is_empty() && !entry.status().is_ignored())
+        .flat_map(|entry| {
+            let deltas = entry.head_to_index().into_iter();
+            let deltas = deltas.chain(entry.index_to_workdir());
+            deltas
+                .flat_map(|delta| vec![delta.old_file().path(), delta.new_file().path()])
+                .filter_map(|path| path?.to_str().map(String::from))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    changed.sort();
+    changed.dedup();
+
+    Ok((statuses, changed))
+}
+
+/// Runs `git status`, which honours `core.untrackedCache` and `core.fsmonitor`,
+/// and converts its output into libgit2 statuses and the paths which changed
+fn get_git_cli_statuses(
+    workdir: &Path,
+    settings: StatusSettings,
+) -> Option<(Vec<Status>, Vec<String>)> {
+    let workdir = workdir.to_str()?;
+    let mut args = vec!["-C", workdir, "status", "--porcelain", "-z"];
+    if settings.ignore_submodules {
+        args.push("--ignore-submodules=all");
+    }
+    let output = utils::exec_cmd("git", &args)?;
+
+    let mut entries = output.stdout.split('\0');
+    let mut statuses = Vec::new();
+    let mut changed = Vec::new();
+    while let Some(entry) = entries.next() {
+        if entry.len() < 3 {
+            continue;
+        }
+        let mut xy = entry.chars();
+        let (x, y) = (xy.next()?, xy.next()?);
+        changed.push(entry[3..].to_string());
+        // Renames and copies are followed by the original path
+        if x == 'R' || x == 'C' {
+            changed.extend(entries.next().map(String::from));
+        }
+        statuses.push(porcelain_to_status(x, y));
+    }
+
+    Some((statuses, changed))
+}
+
+/// Converts the `XY` code of `git status --porcelain` into a libgit2 status
+fn porcelain_to_status(x: char, y: char) -> Status {
+    match (x, y) {
+        ('?', '?') => return Status::WT_NEW,
+        ('!', '!') => return Status::IGNORED,
+        ('D', 'D') | ('A', 'A') | ('U', _) | (_, 'U') => return Status::CONFLICTED,
+        _ => {}
+    }
+
+    let index = match x {
+        'M' => Status::INDEX_MODIFIED,
+        'A' => Status::INDEX_NEW,
+        'D' => Status::INDEX_DELETED,
+        'R' => Status::INDEX_RENAMED,
+        'T' => Status::INDEX_TYPECHANGE,
+        _ => Status::empty(),
+    };
+    let worktree = match y {
+        'M' => Status::WT_MODIFIED,
+        'A' => Status::WT_NEW,
+        'D' => Status::WT_DELETED,
+        'R' => Status::WT_RENAMED,
+        'T' => Status::WT_TYPECHANGE,
+        _ => Status::empty(),
+    };
+
+    index | worktree
+}
+
+/// Compares the index with HEAD, without looking at the working tree at all.
+///
+/// Every difference between them is counted as `staged`, and the status is
+/// marked as `truncated`, since modified and untracked files can't be found
+/// without looking at the working tree.
+fn get_index_status(
+    repository: &mut Repository,
+    settings: StatusSettings,
+) -> Result<RepoStatus, git2::Error> {
+    let index = repository.index()?;
+    let conflicted = if index.has_conflicts() {
+        index.conflicts()?.count()
+    } else {
+        0
+    };
+
+    let staged = {
+        let head_tree = repository
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_tree().ok());
+        let mut diff_options = git2::DiffOptions::new();
+        diff_options.ignore_submodules(settings.ignore_submodules);
+        repository
+            .diff_tree_to_index(head_tree.as_ref(), Some(&index), Some(&mut diff_options))?
+            .deltas()
+            .len()
+    };
+
+    Ok(RepoStatus {
+        conflicted,
+        staged,
+        stashed: stashed_count(repository)?,
+        truncated: true,
+        ..RepoStatus::default()
+    })
+}
+
+fn count_statuses(statuses: Vec<Status>) -> HashMap<&'static str, usize> {
+    let mut predicates: HashMap<&'static str, fn(git2::Status) -> bool> = HashMap::new();
+    predicates.insert("conflicted", is_conflicted);
+    predicates.insert("deleted", is_deleted);
+    predicates.insert("renamed", is_renamed);
+    predicates.insert("modified", is_modified);
+    predicates.insert("staged", is_staged);
+    predicates.insert("untracked", is_untracked);
+
+    statuses.iter().fold(HashMap::new(), |mut map, status| {
+        for (key, predicate) in predicates.iter() {
+            if predicate(*status) {
+                let entry = map.entry(key).or_insert(0);
+                *entry += 1;
+            }
+        }
+        map
+    })
+}
+
+fn is_conflicted(status: Status) -> bool {
+    status.is_conflicted()
+}
+
+fn is_deleted(status: Status) -> bool {
+    status.is_wt_deleted() || status.is_index_deleted()
+}
+
+fn is_renamed(status: Status) -> bool {
+    status.is_wt_renamed() || status.is_index_renamed()
+}
+
+fn is_modified(status: Status) -> bool {
+    status.is_wt_modified()
+}
+
+fn is_staged(status: Status) -> bool {
+    status.is_index_modified() || status.is_index_new()
+}
+
+fn is_untracked(status: Status) -> bool {
+    status.is_wt_new()
+}
+
+fn stashed_count(repository: &mut Repository) -> Result<usize, git2::Error> {
+    let mut count = 0;
+    repository.stash_foreach(|_, _, _| {
+        count += 1;
+        true
+    })?;
+    Result::Ok(count)
+}
+
+/// Compares the current branch with the branch it is tracking to determine how
+/// far ahead or behind it is in relation
+fn get_ahead_behind(
+    repository: &Repository,
+    branch_name: &str,
+) -> Result<(usize, usize), git2::Error> {
+    let branch_object = repository.revparse_single(branch_name)?;
+    let tracking_branch_name = format!("{}@{{upstream}}", branch_name);
+    let tracking_object = repository.revparse_single(&tracking_branch_name)?;
+
+    let branch_oid = branch_object.id();
+    let tracking_oid = tracking_object.id();
+
+    repository.graph_ahead_behind(branch_oid, tracking_oid)
+}
+
+/// Gets the names of the tags pointing at HEAD, sorted. Tag refs are compared by
+/// their target, or the target packed-refs records for an annotated tag, so only
+/// loose annotated tags have to be read, rather than peeling every tag ref.
+fn get_head_tags(repository: &Repository) -> Option<Vec<String>> {
+    let head = repository.head().ok()?.target()?;
+    let odb = repository.odb().ok()?;
+
+    let points_at_head = |reference: &Reference| {
+        let target = match reference.target() {
+            Some(target) => target,
+            None => return false,
+        };
+        if target == head {
+            return true;
+        }
+        match reference.target_peel() {
+            Some(peeled) => peeled == head,
+            None => match odb.read_header(target) {
+                Ok((_, ObjectType::Tag)) => repository
+                    .find_tag(target)
+                    .map(|tag| tag.target_id() == head)
+                    .unwrap_or(false),
+                _ => false,
+            },
+        }
+    };
+
+    let mut tags: Vec<String> = repository
+        .references_glob("refs/tags/*")
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(points_at_head)
+        .filter_map(|reference| reference.shorthand().map(String::from))
+        .collect();
+    tags.sort();
+    Some(tags)
+}
+
+fn get_upstream(repository: &Repository, branch_name: &str) -> Option<Upstream> {
+    let config = repository.config().ok()?;
+    let remote_name = config
+        .get_string(&format!("branch.{}.remote", branch_name))
+        .ok()?;
+    let merge = config
+        .get_string(&format!("branch.{}.merge", branch_name))
+        .ok()?;
+
+    Some(Upstream {
+        remote_name,
+        branch: merge.trim_start_matches("refs/heads/").to_string(),
+    })
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Shell {
     Bash,
@@ -337,6 +1241,10 @@ pub enum Shell {
     Ion,
     PowerShell,
     Zsh,
+    Tcsh,
+    Nu,
+    Elvish,
+    Xonsh,
     Unknown,
 }
 
@@ -364,6 +1272,7 @@ mod tests {
         assert_eq!(
             ScanDir {
                 dir_contents: &empty_dc,
+                parents: &[],
                 files: &["package.json"],
                 extensions: &["js"],
                 folders: &["node_modules"],
@@ -378,6 +1287,7 @@ mod tests {
         assert_eq!(
             ScanDir {
                 dir_contents: &rust_dc,
+                parents: &[],
                 files: &["package.json"],
                 extensions: &["js"],
                 folders: &["node_modules"],
@@ -392,6 +1302,7 @@ mod tests {
         assert_eq!(
             ScanDir {
                 dir_contents: &java_dc,
+                parents: &[],
                 files: &["package.json"],
                 extensions: &["js"],
                 folders: &["node_modules"],
@@ -406,6 +1317,7 @@ mod tests {
         assert_eq!(
             ScanDir {
                 dir_contents: &node_dc,
+                parents: &[],
                 files: &["package.json"],
                 extensions: &["js"],
                 folders: &["node_modules"],
@@ -417,4 +1329,74 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_scan_dir_depth() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = testdir(&["README.md", "app/package.json", "app/src/deep/main.rs"])?;
+
+        let shallow = DirContents::from_path(dir.path())?;
+        assert!(!shallow.has_file_name("package.json"));
+
+        let one_level =
+            DirContents::from_path_with_timeout(dir.path(), Duration::from_secs(30), 1, None)?;
+        assert!(one_level.has_file_name("package.json"));
+        assert!(!one_level.has_extension("rs"));
+        // Nested files don't shadow a file at the top level
+        assert!(!one_level.has_file("package.json"));
+
+        let three_levels =
+            DirContents::from_path_with_timeout(dir.path(), Duration::from_secs(30), 3, None)?;
+        assert!(three_levels.has_extension("rs"));
+
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_dir_depth_honours_gitignore() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = testdir(&[".gitignore", "app/mix.exs", "target/debug/build.rs"])?;
+        fs::write(dir.path().join(".gitignore"), "target/\n*.exs\n")?;
+        let repository = Repository::init(dir.path())?;
+
+        let contents = DirContents::from_path_with_timeout(
+            dir.path(),
+            Duration::from_secs(30),
+            3,
+            Some(&repository),
+        )?;
+        assert!(contents.has_folder("target"));
+        assert!(!contents.has_extension("rs"));
+        assert!(!contents.has_file_name("mix.exs"));
+
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_dir_parents() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = testdir(&["Cargo.toml", "src/main.rs", "src/bin/tool.rs"])?;
+        let parent_dc = DirContents::from_path(dir.path())?;
+        let current_dc = DirContents::from_path(&dir.path().join("src/bin"))?;
+
+        let scan_dir = |parents| ScanDir {
+            dir_contents: &current_dc,
+            parents,
+            files: &["Cargo.toml"],
+            extensions: &[],
+            folders: &["target"],
+        };
+        assert!(!scan_dir(&[]).is_match());
+        assert!(scan_dir(std::slice::from_ref(&parent_dc)).is_match());
+
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_shell_from_name() {
+        assert_eq!(Context::shell_from_name("bash"), Shell::Bash);
+        assert_eq!(Context::shell_from_name("-zsh"), Shell::Zsh);
+        assert_eq!(Context::shell_from_name("pwsh.exe"), Shell::PowerShell);
+        assert_eq!(Context::shell_from_name("cargo"), Shell::Unknown);
+    }
 }
@@ -5,6 +5,7 @@ use std::collections::BTreeMap;
 
 use crate::config::parse_style_string;
 use crate::segment::Segment;
+use crate::utils;
 
 use super::model::*;
 use super::parser::{parse, Rule};
@@ -41,8 +42,9 @@ impl<'a> StringFormatter<'a> {
 
     /// Maps variable name to its value
     pub fn map(mut self, mapper: impl Fn(&str) -> Option<String> + Sync) -> Self {
+        let trace = utils::current_trace();
         self.variables.par_iter_mut().for_each(|(key, value)| {
-            *value = mapper(key).map(VariableValue::Plain);
+            *value = utils::in_trace(trace.clone(), || mapper(key)).map(VariableValue::Plain);
         });
         self
     }
@@ -52,8 +54,9 @@ impl<'a> StringFormatter<'a> {
         mut self,
         mapper: impl Fn(&str) -> Option<Vec<Segment>> + Sync,
     ) -> Self {
+        let trace = utils::current_trace();
         self.variables.par_iter_mut().for_each(|(key, value)| {
-            *value = mapper(key).map(VariableValue::Styled);
+            *value = utils::in_trace(trace.clone(), || mapper(key)).map(VariableValue::Styled);
         });
         self
     }
@@ -185,6 +188,7 @@ fn _new_segment(name: String, value: String, style: Option<Style>) -> Segment {
         _name: name,
         value,
         style,
+        hyperlink: None,
     }
 }
 
@@ -1,3 +1,6 @@
+use crate::config::{RootModuleConfig, StarshipConfig};
+use crate::configs::title::TitleConfig;
+use crate::print::ASYNC_PENDING_STATUS;
 use std::ffi::OsStr;
 use std::path::Path;
 use std::{env, io};
@@ -113,6 +116,27 @@ fi"#,
             let script = format!("eval $({} init ion --print-full-init)", starship);
             Some(script)
         }
+        Some("tcsh") => {
+            let script = format!("eval `(\"{}\" init tcsh --print-full-init)`", starship);
+            Some(script)
+        }
+        Some("nu") => {
+            // Nushell can only source files, so the full script is printed straight away
+            // and the user saves it to a file. See the comment at the top of starship.nu.
+            print_full_init(NU_INIT)?;
+            None
+        }
+        Some("elvish") => {
+            let script = format!(
+                "eval (\"{}\" init elvish --print-full-init | slurp)",
+                starship
+            );
+            Some(script)
+        }
+        Some("xonsh") => {
+            let script = format!("execx($(\"{}\" init xonsh --print-full-init))", starship);
+            Some(script)
+        }
         None => {
             println!(
                 "Invalid shell name provided: {}\\n\
@@ -126,7 +150,8 @@ fi"#,
         Some(shell_basename) => {
             println!(
                 "printf \"\\n{0} is not yet supported by starship.\\n\
-                 For the time being, we support bash, zsh, fish, and ion.\\n\
+                 For the time being, we support bash, zsh, fish, powershell, ion, \
+                 tcsh, nu, elvish and xonsh.\\n\
                  Please open an issue in the starship repo if you would like to \
                  see support for {0}:\\nhttps://github.com/starship/starship/issues/new\"\\n\\n",
                 shell_basename
@@ -143,14 +168,16 @@ fi"#,
 /* This function (called when `--print-full-init` is passed to `starship init`)
 prints out the main initialization script */
 pub fn init_main(shell_name: &str) -> io::Result<()> {
-    let starship_path = path_to_starship()?.replace("\"", "\"'\"'\"");
-
     let setup_script = match shell_name {
         "bash" => Some(BASH_INIT),
         "zsh" => Some(ZSH_INIT),
         "fish" => Some(FISH_INIT),
         "powershell" => Some(PWSH_INIT),
         "ion" => Some(ION_INIT),
+        "tcsh" => Some(TCSH_INIT),
+        "nu" => Some(NU_INIT),
+        "elvish" => Some(ELVISH_INIT),
+        "xonsh" => Some(XONSH_INIT),
         _ => {
             println!(
                 "printf \"Shell name detection failed on phase two init.\\n\
@@ -161,14 +188,45 @@ pub fn init_main(shell_name: &str) -> io::Result<()> {
         }
     };
     if let Some(script) = setup_script {
-        // Set up quoting for starship path in case it has spaces.
-        let starship_path_string = format!("\"{}\"", starship_path);
-        let script = script.replace("::STARSHIP::", &starship_path_string);
-        print!("{}", script);
+        print_full_init(script)?;
     };
     Ok(())
 }
 
+/* Replaces the tokens in an init script and prints it */
+fn print_full_init(script: &str) -> io::Result<()> {
+    let starship_path = path_to_starship()?.replace("\"", "\"'\"'\"");
+
+    // Set up quoting for starship path in case it has spaces.
+    let starship_path_string = format!("\"{}\"", starship_path);
+
+    let config = StarshipConfig::initialize();
+    let title_config = TitleConfig::try_load(config.get_module_config("title"));
+    let shell_integration = config.get_root_config().shell_integration;
+
+    let script = replace_tokens(
+        script,
+        &starship_path_string,
+        !title_config.disabled,
+        shell_integration,
+    );
+    print!("{}", script);
+    Ok(())
+}
+
+fn replace_tokens(
+    script: &str,
+    starship: &str,
+    title_enabled: bool,
+    shell_integration: bool,
+) -> String {
+    script
+        .replace("::STARSHIP::", starship)
+        .replace("::ASYNC_PENDING::", &ASYNC_PENDING_STATUS.to_string())
+        .replace("::TITLE_ENABLED::", &title_enabled.to_string())
+        .replace("::SHELL_INTEGRATION::", &shell_integration.to_string())
+}
+
 /* GENERAL INIT SCRIPT NOTES
 
 Each init script will be passed as-is. Global notes for init scripts are in this
@@ -178,10 +236,33 @@ JOBS: The argument to `--jobs` is quoted because MacOS's `wc` leaves whitespace
 in the output. We pass it to starship and do the whitespace removal in Rust,
 to avoid the cost of an additional shell fork every shell draw.
 
+ASYNC: zsh and fish call `starship prompt --async=fast`, which exits with
+`::ASYNC_PENDING::` if slow modules were left out. The script then runs
+`starship prompt --async=full` in the background and redraws the prompt with its
+output once it finishes: zsh watches a file descriptor with `zle -F` and calls
+`zle reset-prompt`, fish is sent SIGUSR1 and calls `commandline -f repaint`.
+
+TCSH: The script is evaluated by `eval` with command substitution, which joins
+it into a single line, so every command ends with a semicolon and it can't
+contain comments. The `precmd` alias saves the status and renders the prompt,
+`postcmd` starts the timer. Both keep any aliases the user had already set.
+
+TITLE: The prompt sets the terminal title, if `[title]` is enabled. While a
+command runs, bash and zsh show it in the title by calling `starship title
+--command` before running it, and fish calls it from `fish_title`. Whether the
+title is enabled is read from the config once, when the script is printed, and
+`::TITLE_ENABLED::` is replaced by `true` or `false` so the extra process is
+only started when it is needed.
+
+SHELL INTEGRATION: With `shell_integration` enabled, the prompt is marked with
+OSC 133 sequences, and bash, zsh and fish print the mark for the start of a
+command's output before running it. `::SHELL_INTEGRATION::` is replaced by
+`true` or `false`, in the same way as `::TITLE_ENABLED::`.
+
 Note that the init scripts are not in their final form--they are processed by
 `starship init` prior to emitting the final form. In this processing, some tokens
 are replaced, e.g. `::STARSHIP::` is replaced by the full path to the
-starship binary.
+starship binary, and `::ASYNC_PENDING::` by the exit status described above.
 */
 
 const BASH_INIT: &str = include_str!("starship.bash");
@@ -193,3 +274,75 @@ const FISH_INIT: &str = include_str!("starship.fish");
 const PWSH_INIT: &str = include_str!("starship.ps1");
 
 const ION_INIT: &str = include_str!("starship.ion");
+
+const TCSH_INIT: &str = include_str!("starship.tcsh");
+
+const NU_INIT: &str = include_str!("starship.nu");
+
+const ELVISH_INIT: &str = include_str!("starship.elv");
+
+const XONSH_INIT: &str = include_str!("starship.xsh");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_INITS: [&str; 9] = [
+        BASH_INIT,
+        ZSH_INIT,
+        FISH_INIT,
+        PWSH_INIT,
+        ION_INIT,
+        TCSH_INIT,
+        NU_INIT,
+        ELVISH_INIT,
+        XONSH_INIT,
+    ];
+
+    fn full_init(script: &str) -> String {
+        replace_tokens(script, "\"/usr/bin/starship\"", false, false)
+    }
+
+    #[test]
+    fn test_replace_tokens() {
+        let tokens = [
+            "::STARSHIP::",
+            "::ASYNC_PENDING::",
+            "::TITLE_ENABLED::",
+            "::SHELL_INTEGRATION::",
+        ];
+        for script in ALL_INITS.iter() {
+            let script = full_init(script);
+            assert!(tokens.iter().all(|token| !script.contains(token)));
+        }
+    }
+
+    #[test]
+    fn test_nu_init() {
+        let script = full_init(NU_INIT);
+        assert!(script.contains("^\"/usr/bin/starship\" prompt"));
+        assert!(script.contains("$\"--status=($env.LAST_EXIT_CODE)\""));
+        assert!(script.contains("$\"--cmd-duration=($env.CMD_DURATION_MS)\""));
+        assert!(script.contains("$\"--path=($env.PWD)\""));
+        assert!(script.contains("$env.STARSHIP_SHELL = \"nu\""));
+    }
+
+    #[test]
+    fn test_elvish_init() {
+        let script = full_init(ELVISH_INIT);
+        assert!(script.contains(
+            "\"/usr/bin/starship\" prompt --status=$starship-status --jobs=$num-bg-jobs --cmd-duration=$duration"
+        ));
+        assert!(script.contains("set-env STARSHIP_SHELL \"elvish\""));
+    }
+
+    #[test]
+    fn test_xonsh_init() {
+        let script = full_init(XONSH_INIT);
+        assert!(script.contains(
+            "$(\"/usr/bin/starship\" prompt --status=@(status) --cmd-duration=@(duration) --jobs=@(jobs) --keymap=@(keymap))"
+        ));
+        assert!(script.contains("$PROMPT = __starship_prompt"));
+        assert!(script.contains("$STARSHIP_SHELL = \"xonsh\""));
+    }
+}
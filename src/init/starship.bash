@@ -21,29 +21,98 @@ starship_preexec() {
     if [ "$PREEXEC_READY" = "true" ]; then
         PREEXEC_READY=false
         STARSHIP_START_TIME=$(::STARSHIP:: time)
+
+        # Show the command in the terminal title while it runs
+        if ::TITLE_ENABLED::; then
+            ::STARSHIP:: title --command="$BASH_COMMAND"
+        fi
+        # Tell the terminal that the command's output starts here
+        if ::SHELL_INTEGRATION::; then
+            printf '\e]133;C\a'
+        fi
     fi
 
     : "$PREV_LAST_ARG"
 }
 
+# Readline can't redraw PS1 when the vi mode changes, but it does redraw its
+# mode strings, which it puts at the start of the prompt's last line. So in vi
+# mode, starship renders the prompt for both keymaps at once, separated by a
+# record separator, and the last lines are moved into them.
+starship_vi_prompt() {
+    local prompt insert command
+    prompt="$(::STARSHIP:: prompt --keymap=vi "$@")"
+    insert="${prompt%%$'\036'*}"
+    command="${prompt#*$'\036'}"
+
+    if [[ $insert == *$'\n'* ]]; then
+        PS1="${insert%$'\n'*}"$'\n'
+    else
+        # Readline leaves out the mode string if the prompt is empty
+        PS1='\[\]'
+    fi
+    bind "set vi-ins-mode-string \"$(starship_mode_string "${insert##*$'\n'}")\""
+    bind "set vi-cmd-mode-string \"$(starship_mode_string "${command##*$'\n'}")\""
+    bind "set show-mode-in-prompt on"
+    STARSHIP_MODE_STRINGS=true
+}
+
+# Mode strings are shown without the decoding and expansion PS1 goes through, so
+# starship's escaping for those is undone. They mark unprintable text with \1 and
+# \2 rather than \[ and \], and `bind` needs backslashes and quotes to be escaped.
+starship_mode_string() {
+    local text="$1"
+    if shopt -q promptvars; then
+        text="${text//'\\\\'/$'\037'}"
+        text="${text//'\\$'/'$'}"
+        text="${text//'\\`'/'`'}"
+    else
+        text="${text//'\\'/$'\037'}"
+    fi
+    text="${text//'\['/$'\001'}"
+    text="${text//'\]'/$'\002'}"
+    text="${text//$'\037'/'\\'}"
+    printf '%s' "${text//'"'/'\"'}"
+}
+
+# Stop showing the mode strings if the user has switched back to emacs mode
+starship_mode_strings_off() {
+    if [[ $STARSHIP_MODE_STRINGS ]]; then
+        bind "set show-mode-in-prompt off"
+        unset STARSHIP_MODE_STRINGS
+    fi
+}
+
 # Will be run before the prompt is drawn
 starship_precmd() {
-    # Save the status, because commands in this pipeline will change $?
-    STATUS=$?
+    # Save the status, because commands in this pipeline will change $? and
+    # $PIPESTATUS. bash-preexec has already run commands, but saved them for us.
+    STATUS=$? STARSHIP_PIPE_STATUS="${BP_PIPESTATUS[*]-${PIPESTATUS[*]}}"
 
     # Run the bash precmd function, if it's set. If not set, evaluates to no-op
     "${starship_precmd_user_func-:}"
 
     eval "$_PRESERVED_PROMPT_COMMAND"
 
+    local starship_args=(--status=$STATUS --pipestatus="$STARSHIP_PIPE_STATUS" --jobs="$(jobs -p | wc -l)")
+    # Text in the prompt is escaped for parameter expansion, unless it's turned off
+    if ! shopt -q promptvars; then
+        starship_args+=(--no-promptvars)
+    fi
+
     # Prepare the timer data, if needed.
     if [[ $STARSHIP_START_TIME ]]; then
         STARSHIP_END_TIME=$(::STARSHIP:: time)
         STARSHIP_DURATION=$((STARSHIP_END_TIME - STARSHIP_START_TIME))
-        PS1="$(::STARSHIP:: prompt --status=$STATUS --jobs="$(jobs -p | wc -l)" --cmd-duration=$STARSHIP_DURATION)"
+        starship_args+=(--cmd-duration=$STARSHIP_DURATION)
         unset STARSHIP_START_TIME
+    fi
+
+    if [[ :$SHELLOPTS: == *:vi:* ]]; then
+        starship_vi_prompt "${starship_args[@]}"
     else
-        PS1="$(::STARSHIP:: prompt --status=$STATUS --jobs="$(jobs -p | wc -l)")"
+        starship_mode_strings_off
+        PS1="$(::STARSHIP:: prompt "${starship_args[@]}")"
     fi
     PREEXEC_READY=true  # Signal that we can safely restart the timer
 }
@@ -1,14 +1,71 @@
 function fish_prompt
+    # `set` keeps $status, but not $pipestatus, so save both before anything else
+    set -l pipe_status $pipestatus
+    set -l exit_code $status
     switch "$fish_key_bindings"
         case fish_hybrid_key_bindings fish_vi_key_bindings
             set keymap "$fish_bind_mode"
         case '*'
             set keymap insert
     end
-    set -l exit_code $status
     # Account for changes in variable name between v2.7 and v3.0
     set -l starship_duration "$CMD_DURATION$cmd_duration"
-    ::STARSHIP:: prompt --status=$exit_code --keymap=$keymap --cmd-duration=$starship_duration --jobs=(count (jobs -p))
+
+    # A background render finished and asked for a repaint, so show its output
+    if set -q __starship_async_ready
+        set -e __starship_async_ready
+        command cat $__starship_async_file
+        return
+    end
+
+    ::STARSHIP:: prompt --async=fast --status=$exit_code --pipestatus="$pipe_status" --keymap=$keymap --cmd-duration=$starship_duration --jobs=(count (jobs -p))
+
+    # Slow modules were replaced by placeholders, so render them in the background
+    if test $status -eq ::ASYNC_PENDING::
+        __starship_async_start --status=$exit_code --pipestatus="$pipe_status" --keymap=$keymap --cmd-duration=$starship_duration --jobs=(count (jobs -p))
+    end
+end
+
+# Render the full prompt into a file in the background, then signal this shell to repaint.
+# The wrapper passes SIGTERM on to starship, so __starship_async_stop stops the render itself.
+function __starship_async_start
+    __starship_async_stop
+    env STARSHIP_ASYNC_FILE=$__starship_async_file STARSHIP_ASYNC_PID=$fish_pid \
+        sh -c '"$0" prompt --async=full "$@" > "$STARSHIP_ASYNC_FILE" & pid=$!; trap "kill \$pid" TERM; wait $pid && kill -s USR1 "$STARSHIP_ASYNC_PID"' ::STARSHIP:: $argv &
+    set -g __starship_async_pid $last_pid
+    disown $__starship_async_pid
+end
+
+# Kill a background render which is still in flight, so it can't repaint a newer prompt
+function __starship_async_stop --on-event fish_preexec
+    if set -q __starship_async_pid
+        command kill $__starship_async_pid 2>/dev/null
+        set -e __starship_async_pid
+    end
+    set -e __starship_async_ready
+end
+
+function __starship_async_done --on-signal SIGUSR1
+    set -e __starship_async_pid
+    set -g __starship_async_ready 1
+    commandline -f repaint
+end
+
+set -g __starship_async_file (command mktemp)
+
+# fish sets the terminal title from the output of fish_title, which is given the
+# running command, if there is one
+if ::TITLE_ENABLED::
+    function fish_title
+        ::STARSHIP:: title --plain --command="$argv[1]"
+    end
+end
+
+# Tell the terminal that the command's output starts here
+if ::SHELL_INTEGRATION::
+    function __starship_mark_output --on-event fish_preexec
+        printf '\e]133;C\a'
+    end
 end
 
 # disable virtualenv prompt, it breaks starship
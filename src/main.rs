@@ -32,6 +32,26 @@ fn main() {
         .help("The status code of the previously run command")
         .takes_value(true);
 
+    let shell_override_arg = Arg::with_name("shell")
+        .long("shell")
+        .value_name("SHELL")
+        .help(
+            "The shell to render the prompt for, instead of $STARSHIP_SHELL or the parent process",
+        )
+        .takes_value(true);
+
+    let no_promptvars_arg = Arg::with_name("no_promptvars").long("no-promptvars").help(
+        "bash's promptvars or zsh's prompt_subst option is off, so the prompt is not escaped for parameter expansion",
+    );
+
+    let pipestatus_arg = Arg::with_name("pipestatus")
+        .long("pipestatus")
+        .value_name("PIPESTATUS")
+        .help(
+            "The status codes of each command in the previously run pipeline, separated by spaces",
+        )
+        .takes_value(true);
+
     let path_arg = Arg::with_name("path")
         .short("p")
         .long("path")
@@ -42,7 +62,7 @@ fn main() {
     let shell_arg = Arg::with_name("shell")
         .value_name("SHELL")
         .help(
-            "The name of the currently running shell\nCurrently supported options: bash, zsh, fish, powershell, ion",
+            "The name of the currently running shell\nCurrently supported options: bash, zsh, fish, powershell, ion, tcsh, nu, elvish, xonsh",
         )
         .required(true);
 
@@ -57,8 +77,8 @@ fn main() {
         .short("k")
         .long("keymap")
         .value_name("KEYMAP")
-        // fish/zsh only
-        .help("The keymap of fish/zsh")
+        // fish/zsh/bash/xonsh only
+        .help("The keymap of fish/zsh/bash, used to show their vi mode")
         .takes_value(true);
 
     let jobs_arg = Arg::with_name("jobs")
@@ -68,6 +88,23 @@ fn main() {
         .help("The number of currently running jobs")
         .takes_value(true);
 
+    let async_arg = Arg::with_name("async")
+        .long("async")
+        .value_name("PHASE")
+        .help("Render slow modules asynchronously: `fast` shows placeholders for them, `full` renders every module")
+        .possible_values(&["fast", "full"])
+        .takes_value(true);
+
+    let command_arg = Arg::with_name("command")
+        .long("command")
+        .value_name("COMMAND")
+        .help("The command which is about to run")
+        .takes_value(true);
+
+    let plain_arg = Arg::with_name("plain")
+        .long("plain")
+        .help("Print the title without the escape sequence which sets it");
+
     let init_scripts_arg = Arg::with_name("print_full_init")
         .long("print-full-init")
         .help("Print the main initialization script (as opposed to the init stub)");
@@ -84,6 +121,11 @@ fn main() {
             .subcommand(
                 SubCommand::with_name("init")
                     .about("Prints the shell function used to execute starship")
+                    .after_help(
+                        "Nushell and elvish don't tell the prompt which vi mode they are in, \
+                         so the character module always shows its insert mode symbol there. \
+                         Nushell's background jobs aren't counted, so the jobs module isn't shown there either.",
+                    )
                     .arg(&shell_arg)
                     .arg(&init_scripts_arg),
             )
@@ -91,10 +133,14 @@ fn main() {
                 SubCommand::with_name("prompt")
                     .about("Prints the full starship prompt")
                     .arg(&status_code_arg)
+                    .arg(&pipestatus_arg)
                     .arg(&path_arg)
                     .arg(&cmd_duration_arg)
                     .arg(&keymap_arg)
-                    .arg(&jobs_arg),
+                    .arg(&jobs_arg)
+                    .arg(&shell_override_arg)
+                    .arg(&no_promptvars_arg)
+                    .arg(&async_arg),
             )
             .subcommand(
                 SubCommand::with_name("module")
@@ -112,10 +158,13 @@ fn main() {
                             .help("List out all supported modules"),
                     )
                     .arg(&status_code_arg)
+                    .arg(&pipestatus_arg)
                     .arg(&path_arg)
                     .arg(&cmd_duration_arg)
                     .arg(&keymap_arg)
-                    .arg(&jobs_arg),
+                    .arg(&jobs_arg)
+                    .arg(&shell_override_arg)
+                    .arg(&no_promptvars_arg),
             )
             .subcommand(
                 SubCommand::with_name("config")
@@ -140,6 +189,23 @@ fn main() {
             .subcommand(
                 SubCommand::with_name("explain").about("Explains the currently showing modules"),
             )
+            .subcommand(
+                SubCommand::with_name("timings")
+                    .about("Prints how long each module took to compute, slowest first")
+                    .arg(&status_code_arg)
+                    .arg(&pipestatus_arg)
+                    .arg(&path_arg)
+                    .arg(&cmd_duration_arg)
+                    .arg(&keymap_arg)
+                    .arg(&jobs_arg),
+            )
+            .subcommand(
+                SubCommand::with_name("title")
+                    .about("Prints the escape sequence which sets the terminal title")
+                    .arg(&path_arg)
+                    .arg(&command_arg)
+                    .arg(&plain_arg),
+            )
             .subcommand(
                 SubCommand::with_name("completions")
                     .about("Generate starship shell completions for your shell to stdout")
@@ -198,6 +264,8 @@ fn main() {
             }
         }
         ("explain", Some(sub_m)) => print::explain(sub_m.clone()),
+        ("timings", Some(sub_m)) => print::timings(sub_m.clone()),
+        ("title", Some(sub_m)) => print::title(sub_m.clone()),
         ("completions", Some(sub_m)) => {
             let shell: Shell = sub_m
                 .value_of("shell")
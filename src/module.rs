@@ -1,7 +1,7 @@
 use crate::config::SegmentConfig;
 use crate::context::Shell;
 use crate::segment::Segment;
-use crate::utils::wrap_colorseq_for_shell;
+use crate::utils::{escape_for_shell, wrap_colorseq_for_shell};
 use ansi_term::Style;
 use ansi_term::{ANSIString, ANSIStrings};
 use std::fmt;
@@ -25,8 +25,12 @@ pub const ALL_MODULES: &[&str] = &[
     "env_var",
     "git_branch",
     "git_commit",
+    "git_last_commit",
+    "git_metrics",
+    "git_remote",
     "git_state",
     "git_status",
+    "git_user",
     "golang",
     "haskell",
     "hg_branch",
@@ -50,6 +54,7 @@ pub const ALL_MODULES: &[&str] = &[
     "php",
     "terraform",
     "singularity",
+    "status",
     "time",
     "username",
     "zig",
@@ -152,30 +157,47 @@ impl<'a> Module<'a> {
     /// Returns a vector of colored ANSIString elements to be later used with
     /// `ANSIStrings()` to optimize ANSI codes
     pub fn ansi_strings(&self) -> Vec<ANSIString> {
-        self.ansi_strings_for_shell(Shell::Unknown)
+        self.ansi_strings_for_shell(Shell::Unknown, true)
     }
 
-    pub fn ansi_strings_for_shell(&self, shell: Shell) -> Vec<ANSIString> {
+    /// Returns the ANSIString elements with text, like the name of a branch or
+    /// directory, escaped so that `shell` shows it as it is rather than expanding it
+    pub fn ansi_strings_for_shell(
+        &self,
+        shell: Shell,
+        promptvars: bool,
+    ) -> Vec<ANSIString<'static>> {
         let mut ansi_strings = self
             .segments
             .iter()
-            .map(Segment::ansi_string)
+            .map(|segment| segment.ansi_string_for_shell(shell, promptvars))
             .collect::<Vec<ANSIString>>();
 
-        ansi_strings.insert(0, self.prefix.ansi_string());
-        ansi_strings.push(self.suffix.ansi_string());
+        ansi_strings.insert(0, self.prefix.ansi_string_for_shell(shell, promptvars));
+        ansi_strings.push(self.suffix.ansi_string_for_shell(shell, promptvars));
+        ansi_strings
+    }
 
-        ansi_strings = match shell {
+    /// Returns the ANSIString elements for the prompt of `shell`, escaped like
+    /// `ansi_strings_for_shell`, with color sequences wrapped in the shell's
+    /// escapes for non-printing text
+    pub fn ansi_strings_for_prompt(
+        &self,
+        shell: Shell,
+        promptvars: bool,
+    ) -> Vec<ANSIString<'static>> {
+        let ansi_strings = self.ansi_strings_for_shell(shell, promptvars);
+
+        match shell {
             Shell::Bash => ansi_strings_modified(ansi_strings, shell),
             Shell::Zsh => ansi_strings_modified(ansi_strings, shell),
+            Shell::Tcsh => ansi_strings_modified(ansi_strings, shell),
             _ => ansi_strings,
-        };
-
-        ansi_strings
+        }
     }
 
-    pub fn to_string_without_prefix(&self, shell: Shell) -> String {
-        ANSIStrings(&self.ansi_strings_for_shell(shell)[1..]).to_string()
+    pub fn to_string_without_prefix(&self, shell: Shell, promptvars: bool) -> String {
+        ANSIStrings(&self.ansi_strings_for_prompt(shell, promptvars)[1..]).to_string()
     }
 }
 
@@ -249,6 +271,12 @@ impl Affix {
     pub fn ansi_string(&self) -> ANSIString {
         self.style.paint(&self.value)
     }
+
+    /// Generates the colored ANSIString output, with the value escaped for `shell`.
+    pub fn ansi_string_for_shell(&self, shell: Shell, promptvars: bool) -> ANSIString<'static> {
+        self.style
+            .paint(escape_for_shell(&self.value, shell, promptvars))
+    }
 }
 
 impl fmt::Display for Affix {
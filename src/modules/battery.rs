@@ -1,15 +1,8 @@
-use super::{Context, Module, RootModuleConfig, Shell};
+use super::{Context, Module, RootModuleConfig};
 use crate::configs::battery::BatteryConfig;
 
 /// Creates a module for the battery percentage and charging state
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
-    // TODO: Update when v1.0 printing refactor is implemented to only
-    // print escapes in a prompt context.
-    let percentage_char = match context.shell {
-        Shell::Zsh => "%%", // % is an escape in zsh, see PROMPT in `man zshmisc`
-        _ => "%",
-    };
-
     let battery_status = get_battery_status()?;
     let BatteryStatus { state, percentage } = battery_status;
 
@@ -57,7 +50,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         let mut percent_string = Vec::<String>::with_capacity(2);
         // Round the percentage to a whole number
         percent_string.push(percentage.round().to_string());
-        percent_string.push(percentage_char.to_string());
+        percent_string.push("%".to_string());
         module.create_segment(
             "percentage",
             &battery_config
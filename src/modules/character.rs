@@ -10,31 +10,53 @@ use crate::configs::character::CharacterConfig;
 /// - If the exit-code was anything else, the arrow will be formatted with
 /// `style_failure` (red by default)
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let keymap = context
+        .properties
+        .get("keymap")
+        .map_or("viins", String::as_str);
+    module_for_keymap(context, keymap)
+}
+
+/// Creates the character module as it is shown in `keymap`, rather than in the
+/// keymap the shell reported
+pub fn module_for_keymap<'a>(context: &'a Context, keymap: &str) -> Option<Module<'a>> {
     enum ShellEditMode {
         Normal,
         Insert,
+        Replace,
+        ReplaceOne,
+        Visual,
     };
     const ASSUMED_MODE: ShellEditMode = ShellEditMode::Insert;
-    // TODO: extend config to more modes
 
     let mut module = context.new_module("character");
     let config: CharacterConfig = CharacterConfig::try_load(module.config);
     module.get_prefix().set_value("");
 
     let props = &context.properties;
-    let exit_code_default = std::string::String::from("0");
-    let exit_code = props.get("status_code").unwrap_or(&exit_code_default);
-    let keymap_default = std::string::String::from("viins");
-    let keymap = props.get("keymap").unwrap_or(&keymap_default);
-    let exit_success = exit_code == "0";
+    let exit_code = props.get("status_code").map_or("0", String::as_str);
+    // With `use_pipestatus`, a pipeline failed with the last non-zero code of its commands
+    let pipeline_failed = match context.pipestatus() {
+        Some(pipestatus) if config.use_pipestatus => pipestatus.iter().any(|code| *code != 0),
+        _ => false,
+    };
+    let exit_success = exit_code == "0" && !pipeline_failed;
 
     // Match shell "keymap" names to normalized vi modes
     // NOTE: in vi mode, fish reports normal mode as "default".
     // Unfortunately, this is also the name of the non-vi default mode.
     // We do some environment detection in src/init.rs to translate.
     // The result: in non-vi fish, keymap is always reported as "insert"
-    let mode = match (&context.shell, keymap.as_str()) {
-        (Shell::Fish, "default") | (Shell::Zsh, "vicmd") => ShellEditMode::Normal,
+    // zsh stays in its insert keymap in replace mode, so src/init reports that as "replace".
+    let mode = match (&context.shell, keymap) {
+        (Shell::Fish, "default")
+        | (Shell::Zsh, "vicmd")
+        | (Shell::Zsh, "viopp")
+        | (Shell::Xonsh, "vicmd")
+        | (Shell::Bash, "vi-command") => ShellEditMode::Normal,
+        (Shell::Fish, "replace") | (Shell::Zsh, "replace") => ShellEditMode::Replace,
+        (Shell::Fish, "replace_one") => ShellEditMode::ReplaceOne,
+        (Shell::Fish, "visual") | (Shell::Zsh, "visual") => ShellEditMode::Visual,
         _ => ASSUMED_MODE,
     };
 
@@ -52,6 +74,13 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         match mode {
             ShellEditMode::Normal => module.create_segment("vicmd_symbol", &config.vicmd_symbol),
             ShellEditMode::Insert => module.create_segment("symbol", &config.symbol),
+            ShellEditMode::Replace => {
+                module.create_segment("replace_symbol", &config.replace_symbol)
+            }
+            ShellEditMode::ReplaceOne => {
+                module.create_segment("replace_one_symbol", &config.replace_one_symbol)
+            }
+            ShellEditMode::Visual => module.create_segment("visual_symbol", &config.visual_symbol),
         }
     };
 
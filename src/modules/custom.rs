@@ -1,10 +1,11 @@
 use ansi_term::Color;
 use std::io::Write;
 use std::process::{Command, Output, Stdio};
+use std::time::Instant;
 
 use super::{Context, Module, RootModuleConfig};
 
-use crate::{config::SegmentConfig, configs::custom::CustomConfig};
+use crate::{config::SegmentConfig, configs::custom::CustomConfig, utils};
 
 /// Creates a custom module with some configuration
 ///
@@ -170,11 +171,19 @@ fn shell_command(cmd: &str, shell_args: &[&str]) -> Option<Output> {
     command.ok()?.wait_with_output().ok()
 }
 
+/// Run `shell_command`, recording how long it took for `starship timings`
+fn timed_shell_command(cmd: &str, shell_args: &[&str]) -> Option<Output> {
+    let start = Instant::now();
+    let output = shell_command(cmd, shell_args);
+    utils::trace_command(cmd, &[], start.elapsed());
+    output
+}
+
 /// Execute the given command capturing all output, and return whether it return 0
 fn exec_when(cmd: &str, shell_args: &[&str]) -> bool {
     log::trace!("Running '{}'", cmd);
 
-    if let Some(output) = shell_command(cmd, shell_args) {
+    if let Some(output) = timed_shell_command(cmd, shell_args) {
         if !output.status.success() {
             log::trace!("non-zero exit code '{:?}'", output.status.code());
             log::trace!(
@@ -199,7 +208,7 @@ fn exec_when(cmd: &str, shell_args: &[&str]) -> bool {
 fn exec_command(cmd: &str, shell_args: &[&str]) -> Option<String> {
     log::trace!("Running '{}'", cmd);
 
-    if let Some(output) = shell_command(cmd, shell_args) {
+    if let Some(output) = timed_shell_command(cmd, shell_args) {
         if !output.status.success() {
             log::trace!("Non-zero exit code '{:?}'", output.status.code());
             log::trace!(
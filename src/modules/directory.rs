@@ -8,6 +8,7 @@ use super::{Context, Module};
 use super::utils::directory::truncate;
 use crate::config::{RootModuleConfig, SegmentConfig};
 use crate::configs::directory::DirectoryConfig;
+use crate::utils;
 
 /// Creates a module with the current directory
 ///
@@ -15,7 +16,8 @@ use crate::configs::directory::DirectoryConfig;
 /// **Contraction**
 ///     - Paths beginning with the home directory or with a git repo right
 /// inside the home directory will be contracted to `~`
-///     - Paths containing a git repo will contract to begin at the repo root
+///     - Paths containing a git repo will contract to begin at the repo root, or
+/// at the root of the repo containing it for submodules if configured
 ///
 /// **Substitution**
 /// Paths will undergo user-provided substitutions of substrings
@@ -60,15 +62,23 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
     let repo = &context.get_repo().ok()?;
 
-    let dir_string = match &repo.root {
-        Some(repo_root) if config.truncate_to_repo && (repo_root != &home_dir) => {
+    // Submodules can be contracted to the root of the repository containing them
+    let repo_root = if config.truncate_to_superproject {
+        repo.superproject().or(repo.root.as_deref())
+    } else {
+        repo.root.as_deref()
+    };
+    let repo_root = repo_root.filter(|repo_root| config.truncate_to_repo && repo_root != &home_dir);
+
+    let dir_string = match repo_root {
+        Some(repo_root) => {
             let repo_folder_name = repo_root.file_name().unwrap().to_str().unwrap();
 
             // Contract the path to the git repo root
             contract_path(current_dir, repo_root, repo_folder_name)
         }
         // Contract the path to the home directory
-        _ => contract_path(current_dir, &home_dir, HOME_SYMBOL),
+        None => contract_path(current_dir, &home_dir, HOME_SYMBOL),
     };
 
     let substituted_dir = substitute_path(dir_string, &config.substitutions);
@@ -76,6 +86,15 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     // Truncate the dir string to the maximum number of path components
     let truncated_dir_string = truncate(substituted_dir, config.truncation_length as usize);
 
+    // The symbols mark a path contracted to the root of a worktree or submodule
+    if repo_root.is_some() {
+        if repo.worktree.is_some() {
+            module.create_segment("worktree_symbol", &config.worktree_symbol);
+        } else if repo.superproject().is_some() {
+            module.create_segment("submodule_symbol", &config.submodule_symbol);
+        }
+    }
+
     // Substitutions could have changed the prefix, so don't allow them and
     // fish-style path contraction together
     if config.fish_style_pwd_dir_length > 0 && config.substitutions.is_empty() {
@@ -87,22 +106,28 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             &truncated_dir_string,
         );
 
-        module.create_segment(
+        let segment = module.create_segment(
             "path",
             &SegmentConfig {
                 value: &fish_style_dir,
                 style: None,
             },
         );
+        if config.hyperlink {
+            segment.set_hyperlink(utils::file_uri(current_dir));
+        }
     }
 
-    module.create_segment(
+    let segment = module.create_segment(
         "path",
         &SegmentConfig {
             value: &truncated_dir_string,
             style: None,
         },
     );
+    if config.hyperlink {
+        segment.set_hyperlink(utils::file_uri(current_dir));
+    }
 
     module.get_prefix().set_value(config.prefix);
 
@@ -1,12 +1,18 @@
 use unicode_segmentation::UnicodeSegmentation;
 
+use super::utils::forge::Forge;
 use super::{Context, Module, RootModuleConfig};
 
 use crate::configs::git_branch::GitBranchConfig;
+use crate::context::Repo;
 
 /// Creates a module with the Git branch in the current directory
 ///
-/// Will display the branch name if the current directory is a git repo
+/// Will display the branch name if the current directory is a git repo, followed
+/// by the remote branch it tracks if that has a different name. A detached HEAD
+/// is named by its tag, its `git describe` output or its abbreviated hash.
+/// Linked worktrees and submodules are shown with the name of the worktree or of
+/// the repository containing the submodule.
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("git_branch");
     let config = GitBranchConfig::try_load(module.config);
@@ -31,22 +37,115 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
     let repo = context.get_repo().ok()?;
     let branch_name = repo.branch.as_ref()?;
-    let truncated_graphemes = get_graphemes(&branch_name, len);
+    let is_detached = branch_name == "HEAD";
+    let display_name = if is_detached {
+        repo.detached_head_name(
+            config.detached_tag,
+            config.detached_describe,
+            config.detached_hash_length,
+        )
+        .unwrap_or_else(|| branch_name.clone())
+    } else {
+        branch_name.clone()
+    };
+
+    let truncated_graphemes = get_graphemes(&display_name, len);
     // The truncation symbol should only be added if we truncated
-    let truncated_and_symbol = if len < graphemes_len(&branch_name) {
+    let truncated_and_symbol = if len < graphemes_len(&display_name) {
         truncated_graphemes + &truncation_symbol
     } else {
         truncated_graphemes
     };
 
-    module.create_segment(
+    let segment = module.create_segment(
         "name",
         &config.branch_name.with_value(&truncated_and_symbol),
     );
+    if config.hyperlink {
+        let url = if is_detached {
+            get_commit_url(repo)
+        } else {
+            get_branch_url(repo, branch_name)
+        };
+        if let Some(url) = url {
+            segment.set_hyperlink(url);
+        }
+    }
+
+    // A detached HEAD has no upstream
+    if !is_detached {
+        match repo.upstream() {
+            Some(upstream) if config.always_show_remote || upstream.branch != *branch_name => {
+                module.create_segment("remote_symbol", &config.remote_symbol);
+                // `.` is the remote of a branch which tracks another local branch
+                if upstream.remote_name != "." {
+                    let remote_name = format!("{}/", upstream.remote_name);
+                    module.create_segment(
+                        "remote_name",
+                        &config.remote_name.with_value(&remote_name),
+                    );
+                }
+                module.create_segment(
+                    "remote_branch",
+                    &config.remote_branch.with_value(&upstream.branch),
+                );
+            }
+            Some(_) => {}
+            None => {
+                module.create_segment("no_upstream_symbol", &config.no_upstream_symbol);
+            }
+        }
+    }
+
+    // The name of a linked worktree is only shown once a symbol is set to go before it
+    let worktree = repo
+        .worktree
+        .as_ref()
+        .filter(|_| !config.worktree_symbol.value.is_empty());
+    if let Some(worktree) = worktree {
+        module.create_segment("worktree_symbol", &config.worktree_symbol);
+        module.create_segment("worktree", &config.worktree.with_value(worktree));
+    }
+
+    let superproject_name = repo
+        .superproject()
+        .and_then(|superproject| superproject.file_name()?.to_str());
+    if let Some(superproject_name) = superproject_name {
+        module.create_segment("submodule_symbol", &config.submodule_symbol);
+        module.create_segment(
+            "superproject",
+            &config.superproject.with_value(superproject_name),
+        );
+    }
 
     Some(module)
 }
 
+/// Links to the branch on the forge hosting the repository. If the branch tracks
+/// a remote branch, that one is linked to instead, on the forge of its remote.
+fn get_branch_url(repo: &Repo, branch_name: &str) -> Option<String> {
+    let upstream = repo.upstream();
+    let repository = repo.repository()?;
+
+    match upstream {
+        Some(upstream) => {
+            let forge = match repository.find_remote(&upstream.remote_name) {
+                Ok(remote) => Forge::from_remote_url(remote.url()?)?,
+                Err(_) => Forge::from_repository(&repository)?,
+            };
+            Some(forge.branch_url(&upstream.branch))
+        }
+        None => Some(Forge::from_repository(&repository)?.branch_url(branch_name)),
+    }
+}
+
+/// Links to the commit a detached HEAD points at
+fn get_commit_url(repo: &Repo) -> Option<String> {
+    let repository = repo.repository()?;
+    let head = repository.head().ok()?.peel_to_commit().ok()?;
+    Some(Forge::from_repository(&repository)?.commit_url(&head.id().to_string()))
+}
+
 fn get_graphemes(text: &str, length: usize) -> String {
     UnicodeSegmentation::graphemes(text, true)
         .take(length)
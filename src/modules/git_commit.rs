@@ -1,11 +1,12 @@
+use super::utils::forge::Forge;
 use super::{Context, Module, RootModuleConfig};
-use git2::Repository;
 
 use crate::configs::git_commit::GitCommitConfig;
 
 /// Creates a module with the Git commit in the current directory
 ///
-/// Will display the commit hash if the current directory is a git repo
+/// Will display the commit hash if the current directory is a git repo, and
+/// optionally the tags pointing at it
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("git_commit");
     let config = GitCommitConfig::try_load(module.config);
@@ -21,24 +22,52 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     module.set_style(config.style);
 
     let repo = context.get_repo().ok()?;
-    let repo_root = repo.root.as_ref()?;
-    let git_repo = Repository::open(repo_root).ok()?;
-
-    let is_detached = git_repo.head_detached().ok()?;
+    let detached_name = if config.detached_tag || config.detached_describe {
+        repo.detached_head_name(
+            config.detached_tag,
+            config.detached_describe,
+            config.commit_hash_length,
+        )
+    } else {
+        None
+    };
+    let (is_detached, commit_oid, forge) = {
+        let git_repo = repo.repository()?;
+        let is_detached = git_repo.head_detached().ok()?;
+        let commit_oid = git_repo.head().ok()?.peel_to_commit().ok()?.id();
+        let forge = match config.hyperlink {
+            true => Forge::from_repository(&git_repo),
+            false => None,
+        };
+        (is_detached, commit_oid, forge)
+    };
     if config.only_detached && !is_detached {
         return None;
     };
 
-    let git_head = git_repo.head().ok()?;
-    let head_commit = git_head.peel_to_commit().ok()?;
-    let commit_oid = head_commit.id();
-    module.create_segment(
-        "hash",
-        &config.hash.with_value(&id_to_hex_abbrev(
-            commit_oid.as_bytes(),
-            config.commit_hash_length,
-        )),
-    );
+    let hash = detached_name
+        .unwrap_or_else(|| id_to_hex_abbrev(commit_oid.as_bytes(), config.commit_hash_length));
+    let segment = module.create_segment("hash", &config.hash.with_value(&hash));
+    if let Some(forge) = forge {
+        segment.set_hyperlink(forge.commit_url(&commit_oid.to_string()));
+    }
+
+    if !config.tag_disabled {
+        // A detached HEAD may already be named by its first tag, here or, when
+        // `tag_hide_detached_name` says so, by git_branch
+        let named_by_tag = is_detached
+            && (config.detached_tag || config.detached_describe || config.tag_hide_detached_name);
+        let tags = repo.head_tags();
+        let tags = if named_by_tag {
+            &tags[tags.len().min(1)..]
+        } else {
+            tags
+        };
+        if !tags.is_empty() {
+            module.create_segment("tag_symbol", &config.tag_symbol);
+            module.create_segment("tag", &config.tag.with_value(&tags.join(", ")));
+        }
+    }
 
     Some(module)
 }
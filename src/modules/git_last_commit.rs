@@ -0,0 +1,84 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{Context, Module, RootModuleConfig, SegmentConfig};
+
+use crate::configs::git_last_commit::GitLastCommitConfig;
+
+/// Creates a module with the age of the last commit in the current directory
+///
+/// Will display how long ago HEAD was committed, e.g. `3h`, and optionally by whom.
+/// The style changes once the working tree has been modified for a long time
+/// after that commit, following `uncommitted_display`.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("git_last_commit");
+    let config = GitLastCommitConfig::try_load(module.config);
+
+    let repo = context.get_repo().ok()?;
+    let (commit_time, author) = {
+        let repository = repo.repository()?;
+        let commit = repository.head().ok()?.peel_to_commit().ok()?;
+        let author = commit.author().name().map(String::from);
+        (commit.time().seconds(), author)
+    };
+
+    let now = to_seconds(SystemTime::now())?;
+    let age = (now - commit_time).max(0);
+
+    // The longest threshold the uncommitted work has passed decides the style
+    let uncommitted = if config.uncommitted_display.is_empty() {
+        None
+    } else {
+        repo.last_modified()
+            .and_then(to_seconds)
+            .map(|last_modified| last_modified - commit_time)
+    };
+    let display_style = uncommitted.and_then(|uncommitted| {
+        config
+            .uncommitted_display
+            .iter()
+            .filter(|display| uncommitted >= display.threshold)
+            .max_by_key(|display| display.threshold)
+    });
+    module.set_style(display_style.map_or(config.style, |display| display.style));
+
+    module.get_prefix().set_value(config.prefix);
+    module.create_segment("age", &SegmentConfig::new(&render_age(age)));
+    module.create_segment("age_suffix", &config.age_suffix);
+
+    if let Some(author) = author.filter(|_| config.show_author) {
+        module.create_segment("author_symbol", &config.author_symbol);
+        module.create_segment("author", &SegmentConfig::new(&author));
+    }
+
+    Some(module)
+}
+
+fn to_seconds(time: SystemTime) -> Option<i64> {
+    let seconds = time.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some(seconds as i64)
+}
+
+/// Renders an age in seconds with its largest unit, e.g. `3h`
+fn render_age(seconds: i64) -> String {
+    let units = [(86_400, "d"), (3_600, "h"), (60, "m")];
+
+    units
+        .iter()
+        .find(|(unit, _)| seconds >= *unit)
+        .map(|(unit, suffix)| format!("{}{}", seconds / unit, suffix))
+        .unwrap_or_else(|| format!("{}s", seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_age() {
+        assert_eq!(render_age(0), "0s");
+        assert_eq!(render_age(59), "59s");
+        assert_eq!(render_age(60), "1m");
+        assert_eq!(render_age(3 * 3_600 + 59 * 60), "3h");
+        assert_eq!(render_age(86_400 * 12), "12d");
+    }
+}
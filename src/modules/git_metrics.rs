@@ -0,0 +1,44 @@
+use super::{Context, Module, RootModuleConfig};
+
+use crate::config::SegmentConfig;
+use crate::configs::git_metrics::GitMetricsConfig;
+
+/// Creates a module with the size of the uncommitted changes in the repo
+///
+/// Will display the number of lines added and deleted in the working tree,
+/// unless there are none
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("git_metrics");
+    let config = GitMetricsConfig::try_load(module.config);
+    module.get_prefix().set_value("");
+
+    // The diff shares the budget of the status in large repositories
+    let repo = context.get_repo().ok()?;
+    let (added, deleted) = repo.diff_stats(config.include_staged)?;
+    if added == 0 && deleted == 0 {
+        return None;
+    }
+
+    let show_added = added > 0 || !config.only_nonzero_diffs;
+    let show_deleted = deleted > 0 || !config.only_nonzero_diffs;
+
+    if show_added {
+        let added = format!("{}{}", config.added_symbol, added);
+        module.create_segment(
+            "added",
+            &SegmentConfig::new(&added).with_style(Some(config.added_style)),
+        );
+    }
+    if show_added && show_deleted {
+        module.create_segment("separator", &SegmentConfig::new(" "));
+    }
+    if show_deleted {
+        let deleted = format!("{}{}", config.deleted_symbol, deleted);
+        module.create_segment(
+            "deleted",
+            &SegmentConfig::new(&deleted).with_style(Some(config.deleted_style)),
+        );
+    }
+
+    Some(module)
+}
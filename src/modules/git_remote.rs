@@ -0,0 +1,104 @@
+use git2::{Config, Remote, Repository};
+
+use super::utils::forge::{default_remote, Forge, ForgeKind};
+use super::{Context, Module, RootModuleConfig, SegmentConfig};
+
+use crate::configs::git_remote::GitRemoteConfig;
+
+/// Creates a module with the forge and repository the current branch is pushed to
+///
+/// Will display the owner and name of the repository, e.g. `starship/starship`,
+/// after a symbol for its host. The remote is the one tracked by the current
+/// branch, otherwise `origin` or the only remote.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("git_remote");
+    let config = GitRemoteConfig::try_load(module.config);
+
+    let repo = context.get_repo().ok()?;
+    let forge = {
+        let upstream_remote = repo.upstream().map(|upstream| upstream.remote_name.clone());
+        let repository = repo.repository()?;
+        let remote = match upstream_remote.and_then(|name| repository.find_remote(&name).ok()) {
+            Some(remote) => remote,
+            None => default_remote(&repository)?,
+        };
+        let url = get_push_url(&repository, &remote)?;
+        Forge::from_remote_url(&url)?
+    };
+
+    module.set_style(config.style);
+    module.get_prefix().set_value(config.prefix);
+
+    let symbol = get_symbol(&forge, &config);
+    module.create_segment("symbol", &SegmentConfig::new(symbol));
+
+    if config.show_host {
+        let host = format!("{}/", forge.host);
+        module.create_segment("host", &config.host.with_value(&host));
+    }
+    if let Some(owner) = forge.owner() {
+        let owner = format!("{}/", owner);
+        module.create_segment("owner", &config.owner.with_value(&owner));
+    }
+    module.create_segment("repo", &config.repo.with_value(forge.repo_name()));
+
+    Some(module)
+}
+
+/// Gets the URL a remote is pushed to, like git does. libgit2 applies `insteadOf`
+/// rewrites to the `url`, but `pushInsteadOf` ones only to an explicit `pushurl`,
+/// so without one, `pushInsteadOf` is applied to the `url` here, and takes
+/// precedence over `insteadOf`.
+fn get_push_url(repository: &Repository, remote: &Remote) -> Option<String> {
+    if let Some(pushurl) = remote.pushurl() {
+        return Some(pushurl.to_string());
+    }
+
+    let config = repository.config().ok()?;
+    let original_url = remote
+        .name()
+        .and_then(|name| config.get_string(&format!("remote.{}.url", name)).ok());
+    original_url
+        .and_then(|url| push_instead_of(&config, &url))
+        .or_else(|| remote.url().map(String::from))
+}
+
+/// Rewrites a URL with the `url.<base>.pushInsteadOf` rule with the longest
+/// prefix of it, if one matches
+fn push_instead_of(config: &Config, url: &str) -> Option<String> {
+    let entries = config.entries(Some(r"^url\..*\.pushinsteadof$")).ok()?;
+    let (base, prefix) = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.name()?;
+            let base = name.get("url.".len()..name.len() - ".pushinsteadof".len())?;
+            Some((base.to_string(), entry.value()?.to_string()))
+        })
+        .filter(|(_, prefix)| url.starts_with(prefix.as_str()))
+        .max_by_key(|(_, prefix)| prefix.len())?;
+
+    Some(format!("{}{}", base, &url[prefix.len()..]))
+}
+
+/// Gets the symbol configured for the host of the forge, or for one of its parent
+/// domains, falling back on a symbol for the kind of forge
+fn get_symbol<'a>(forge: &Forge, config: &GitRemoteConfig<'a>) -> &'a str {
+    let mut domain = forge.host.as_str();
+    loop {
+        if let Some(symbol) = config.symbols.get(domain) {
+            return symbol;
+        }
+        match domain.find('.') {
+            Some(dot) => domain = &domain[dot + 1..],
+            None => break,
+        }
+    }
+
+    match forge.kind {
+        ForgeKind::GitLab => "\u{f296} ",
+        ForgeKind::Bitbucket => "\u{f171} ",
+        // Unknown forges are assumed to be like GitHub, so only GitHub itself gets its symbol
+        ForgeKind::GitHub if forge.host == "github.com" => "\u{f09b} ",
+        ForgeKind::GitHub => "",
+    }
+}
@@ -1,5 +1,5 @@
-use git2::RepositoryState;
-use std::path::{Path, PathBuf};
+use git2::{BranchType, Index, Oid, Repository, RepositoryState};
+use std::path::Path;
 
 use super::{Context, Module, RootModuleConfig, SegmentConfig};
 use crate::configs::git_state::GitStateConfig;
@@ -8,6 +8,8 @@ use crate::configs::git_state::GitStateConfig;
 ///
 /// During a git operation it will show: REBASING, BISECTING, MERGING, etc.
 /// If the progress information is available (e.g. rebasing 3/10), it will show that too.
+/// Rebases also show the branch being rebased and what it is rebased onto, merges
+/// show the branches being merged in, and both show the number of conflicted files.
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("git_state");
     let config: GitStateConfig = GitStateConfig::try_load(module.config);
@@ -17,10 +19,12 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     module.get_suffix().set_value(") ");
 
     let repo = context.get_repo().ok()?;
-    let repo_root = repo.root.as_ref()?;
     let repo_state = repo.state?;
+    let repository = repo.repository()?;
+    // Linked worktrees and submodules have a `.git` file pointing to their git directory
+    let git_dir = repository.path();
 
-    let state_description = get_state_description(repo_state, repo_root, config);
+    let state_description = get_state_description(repo_state, git_dir, config.clone());
 
     let label = match &state_description {
         StateDescription::Label(label) => label,
@@ -44,15 +48,163 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         );
     }
 
+    match repo_state {
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge => {
+            let details = get_rebase_details(&repository, git_dir);
+            if let Some(head_name) = details.head_name {
+                module.create_segment("head_name", &SegmentConfig::new(&format!(" {}", head_name)));
+            }
+            if let Some(onto) = details.onto {
+                module.create_segment("onto_symbol", &config.onto_symbol);
+                module.create_segment("onto", &SegmentConfig::new(&onto));
+            }
+            if let Some(step) = details.step.filter(|_| config.show_step) {
+                module.create_segment("step_symbol", &config.step_symbol);
+                module.create_segment("step", &SegmentConfig::new(&step));
+            }
+        }
+        RepositoryState::Merge => {
+            if let Some(merge_heads) = get_merge_heads(&repository, git_dir) {
+                module.create_segment(
+                    "merge_head",
+                    &SegmentConfig::new(&format!(" {}", merge_heads)),
+                );
+            }
+        }
+        _ => {}
+    }
+
+    let conflicted = get_conflicted_count(&repository);
+    if conflicted > 0 {
+        module.create_segment("conflicted_symbol", &config.conflicted_symbol);
+        module.create_segment(
+            "conflicted_count",
+            &SegmentConfig::new(&conflicted.to_string()),
+        );
+    }
+
     Some(module)
 }
 
+/// What is being rebased onto what, read from the files git keeps during a rebase
+#[derive(Default)]
+struct RebaseDetails {
+    /// The branch being rebased
+    head_name: Option<String>,
+    /// The branch, or commit, being rebased onto
+    onto: Option<String>,
+    /// The subject of the commit being applied
+    step: Option<String>,
+}
+
+fn get_rebase_details(repository: &Repository, git_dir: &Path) -> RebaseDetails {
+    let rebase_dir = if git_dir.join("rebase-merge").exists() {
+        git_dir.join("rebase-merge")
+    } else if git_dir.join("rebase-apply").exists() {
+        git_dir.join("rebase-apply")
+    } else {
+        return RebaseDetails::default();
+    };
+
+    let read_file = |name: &str| crate::utils::read_file(rebase_dir.join(name)).ok();
+
+    // `head-name` is `detached HEAD` when rebasing a detached HEAD
+    let head_name = read_file("head-name").and_then(|head_name| {
+        head_name
+            .trim()
+            .strip_prefix("refs/heads/")
+            .map(str::to_string)
+    });
+
+    // Some tools record the name they were given for the base in `onto_name`
+    let onto = read_file("onto_name")
+        .map(|onto_name| {
+            onto_name
+                .trim()
+                .trim_start_matches("refs/heads/")
+                .to_string()
+        })
+        .filter(|onto_name| !onto_name.is_empty())
+        .or_else(|| {
+            read_file("onto")
+                .and_then(|onto| Oid::from_str(onto.trim()).ok())
+                .map(|oid| name_commit(repository, oid))
+        });
+
+    // The last line of `done` is the todo list command being applied, e.g.
+    // `pick 1a2b3c4 Subject`
+    let step = read_file("done").and_then(|done| {
+        let command = done
+            .lines()
+            .rev()
+            .find(|line| !line.is_empty() && !line.starts_with('#'))?
+            .to_string();
+        let mut parts = command.splitn(3, ' ');
+        parts.next()?;
+        Oid::from_str(parts.next()?).ok()?;
+        parts.next().map(str::to_string)
+    });
+
+    RebaseDetails {
+        head_name,
+        onto,
+        step,
+    }
+}
+
+/// Names the commits being merged in, listed in `MERGE_HEAD`
+fn get_merge_heads(repository: &Repository, git_dir: &Path) -> Option<String> {
+    let merge_head = crate::utils::read_file(git_dir.join("MERGE_HEAD")).ok()?;
+    let names: Vec<String> = merge_head
+        .lines()
+        .filter_map(|line| Oid::from_str(line.trim()).ok())
+        .map(|oid| name_commit(repository, oid))
+        .collect();
+
+    if names.is_empty() {
+        None
+    } else {
+        Some(names.join(", "))
+    }
+}
+
+/// Names a commit by a local branch pointing at it, or by its abbreviated hash.
+/// Branches point at commits directly, so their refs are compared without
+/// reading any objects, and remote branches and tags aren't looked at.
+fn name_commit(repository: &Repository, oid: Oid) -> String {
+    repository
+        .branches(Some(BranchType::Local))
+        .ok()
+        .and_then(|mut branches| {
+            branches.find_map(|branch| {
+                let (branch, _) = branch.ok()?;
+                if branch.get().target()? != oid {
+                    return None;
+                }
+                branch.name().ok()?.map(str::to_string)
+            })
+        })
+        .unwrap_or_else(|| oid.to_string().chars().take(7).collect())
+}
+
+/// Counts the files with conflicts in the index
+fn get_conflicted_count(repository: &Repository) -> usize {
+    repository
+        .index()
+        .ok()
+        .filter(Index::has_conflicts)
+        .and_then(|index| index.conflicts().ok().map(Iterator::count))
+        .unwrap_or(0)
+}
+
 /// Returns the state of the current repository
 ///
 /// During a git operation it will show: REBASING, BISECTING, MERGING, etc.
 fn get_state_description<'a>(
     state: RepositoryState,
-    root: &'a std::path::PathBuf,
+    git_dir: &Path,
     config: GitStateConfig<'a>,
 ) -> StateDescription<'a> {
     match state {
@@ -77,16 +229,13 @@ fn get_state_description<'a>(
         RepositoryState::ApplyMailboxOrRebase => {
             StateDescription::Label(StateLabel::new("am_or_rebase", config.am_or_rebase))
         }
-        RepositoryState::Rebase => describe_rebase(root, config.rebase),
-        RepositoryState::RebaseInteractive => describe_rebase(root, config.rebase),
-        RepositoryState::RebaseMerge => describe_rebase(root, config.rebase),
+        RepositoryState::Rebase => describe_rebase(git_dir, config.rebase),
+        RepositoryState::RebaseInteractive => describe_rebase(git_dir, config.rebase),
+        RepositoryState::RebaseMerge => describe_rebase(git_dir, config.rebase),
     }
 }
 
-fn describe_rebase<'a>(
-    root: &'a PathBuf,
-    rebase_config: SegmentConfig<'a>,
-) -> StateDescription<'a> {
+fn describe_rebase<'a>(git_dir: &Path, rebase_config: SegmentConfig<'a>) -> StateDescription<'a> {
     /*
      *  Sadly, libgit2 seems to have some issues with reading the state of
      *  interactive rebases. So, instead, we'll poke a few of the .git files
@@ -95,15 +244,13 @@ fn describe_rebase<'a>(
      *  The following is based heavily on: https://github.com/magicmonty/bash-git-prompt
      */
 
-    let dot_git = root.join(".git");
-
     let has_path = |relative_path: &str| {
-        let path = dot_git.join(Path::new(relative_path));
+        let path = git_dir.join(Path::new(relative_path));
         path.exists()
     };
 
     let file_to_usize = |relative_path: &str| {
-        let path = dot_git.join(Path::new(relative_path));
+        let path = git_dir.join(Path::new(relative_path));
         let contents = crate::utils::read_file(path).ok()?;
         let quantity = contents.trim().parse::<usize>().ok()?;
         Some(quantity)
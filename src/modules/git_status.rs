@@ -1,11 +1,7 @@
-use git2::{Repository, Status};
-
 use super::{Context, Module, RootModuleConfig};
 
 use crate::config::SegmentConfig;
 use crate::configs::git_status::{CountConfig, GitStatusConfig};
-use std::borrow::BorrowMut;
-use std::collections::HashMap;
 
 /// Creates a module with the Git branch in the current directory
 ///
@@ -15,17 +11,19 @@ use std::collections::HashMap;
 ///   - `⇡` – This branch is ahead of the branch being tracked
 ///   - `⇣` – This branch is behind of the branch being tracked
 ///   - `⇕` – This branch has diverged from the branch being tracked
+///   - `↑`/`↓` – This branch is ahead/behind of the base branch in `compare_to`
 ///   - `?` — There are untracked files in the working directory
 ///   - `$` — A stash exists for the local repository
 ///   - `!` — There are file modifications in the working directory
 ///   - `+` — A new file has been added to the staging area
 ///   - `»` — A renamed file has been added to the staging area
 ///   - `✘` — A file's deletion has been added to the staging area
+///   - `*` — The index differs from HEAD, in a repository too large to scan
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let repo = context.get_repo().ok()?;
-    let branch_name = repo.branch.as_ref()?;
-    let repo_root = repo.root.as_ref()?;
-    let mut repository = Repository::open(repo_root).ok()?;
+    if repo.branch.is_none() || repo.root.is_none() {
+        return None;
+    }
 
     let mut module = context.new_module("git_status");
     let config: GitStatusConfig = GitStatusConfig::try_load(module.config);
@@ -40,18 +38,18 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         .set_style(config.style);
     module.set_style(config.style);
 
-    let repo_status = get_repo_status(repository.borrow_mut());
+    let repo_status = repo.status().copied();
     log::debug!("Repo status: {:?}", repo_status);
 
-    let ahead_behind = get_ahead_behind(&repository, branch_name);
-    if ahead_behind == Ok((0, 0)) {
+    let ahead_behind = repo.ahead_behind();
+    if ahead_behind == Some((0, 0)) {
         log::trace!("No ahead/behind found");
     } else {
         log::debug!("Repo ahead/behind: {:?}", ahead_behind);
     }
 
     // Add the conflicted segment
-    if let Ok(repo_status) = repo_status {
+    if let Some(repo_status) = repo_status {
         create_segment_with_count(
             &mut module,
             "conflicted",
@@ -62,7 +60,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 
     // Add the ahead/behind segment
-    if let Ok((ahead, behind)) = ahead_behind {
+    if let Some((ahead, behind)) = ahead_behind {
         let add_ahead = |m: &mut Module<'a>| {
             create_segment_with_count(
                 m,
@@ -107,8 +105,27 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         }
     }
 
+    // Add the segments comparing with the base branch
+    if let Some((ahead, behind)) = repo.ahead_behind_base(&config.compare_to) {
+        log::debug!("Repo ahead/behind base: {:?}", (ahead, behind));
+        create_segment_with_count(
+            &mut module,
+            "base_ahead",
+            ahead,
+            &config.base_ahead,
+            config.base_ahead_count,
+        );
+        create_segment_with_count(
+            &mut module,
+            "base_behind",
+            behind,
+            &config.base_behind,
+            config.base_behind_count,
+        );
+    }
+
     // Add the stashed segment
-    if let Ok(repo_status) = repo_status {
+    if let Some(repo_status) = repo_status {
         create_segment_with_count(
             &mut module,
             "stashed",
@@ -118,8 +135,16 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         );
     }
 
+    // The working tree of large repositories isn't scanned, so only show that
+    // something differs between the index and HEAD
+    if let Some(repo_status) = repo_status.filter(|status| status.truncated) {
+        if repo_status.staged > 0 {
+            module.create_segment("dirty", &config.dirty);
+        }
+    }
+
     // Add all remaining status segments
-    if let Ok(repo_status) = repo_status {
+    if let Some(repo_status) = repo_status.filter(|status| !status.truncated) {
         create_segment_with_count(
             &mut module,
             "deleted",
@@ -186,122 +211,3 @@ fn create_segment_with_count<'a>(
         }
     }
 }
-
-/// Gets the number of files in various git states (staged, modified, deleted, etc...)
-fn get_repo_status(repository: &mut Repository) -> Result<RepoStatus, git2::Error> {
-    let mut status_options = git2::StatusOptions::new();
-
-    match repository.config()?.get_entry("status.showUntrackedFiles") {
-        Ok(entry) => status_options.include_untracked(entry.value() != Some("no")),
-        _ => status_options.include_untracked(true),
-    };
-    status_options
-        .renames_from_rewrites(true)
-        .renames_head_to_index(true)
-        .renames_index_to_workdir(true)
-        .include_unmodified(true);
-
-    let statuses: Vec<Status> = repository
-        .statuses(Some(&mut status_options))?
-        .iter()
-        .map(|s| s.status())
-        .collect();
-
-    if statuses.is_empty() {
-        return Err(git2::Error::from_str("Repo has no status"));
-    }
-
-    let statuses_count = count_statuses(statuses);
-
-    let repo_status: RepoStatus = RepoStatus {
-        conflicted: *statuses_count.get("conflicted").unwrap_or(&0),
-        deleted: *statuses_count.get("deleted").unwrap_or(&0),
-        renamed: *statuses_count.get("renamed").unwrap_or(&0),
-        modified: *statuses_count.get("modified").unwrap_or(&0),
-        staged: *statuses_count.get("staged").unwrap_or(&0),
-        untracked: *statuses_count.get("untracked").unwrap_or(&0),
-        stashed: stashed_count(repository)?,
-    };
-
-    Ok(repo_status)
-}
-
-fn count_statuses(statuses: Vec<Status>) -> HashMap<&'static str, usize> {
-    let mut predicates: HashMap<&'static str, fn(git2::Status) -> bool> = HashMap::new();
-    predicates.insert("conflicted", is_conflicted);
-    predicates.insert("deleted", is_deleted);
-    predicates.insert("renamed", is_renamed);
-    predicates.insert("modified", is_modified);
-    predicates.insert("staged", is_staged);
-    predicates.insert("untracked", is_untracked);
-
-    statuses.iter().fold(HashMap::new(), |mut map, status| {
-        for (key, predicate) in predicates.iter() {
-            if predicate(*status) {
-                let entry = map.entry(key).or_insert(0);
-                *entry += 1;
-            }
-        }
-        map
-    })
-}
-
-fn is_conflicted(status: Status) -> bool {
-    status.is_conflicted()
-}
-
-fn is_deleted(status: Status) -> bool {
-    status.is_wt_deleted() || status.is_index_deleted()
-}
-
-fn is_renamed(status: Status) -> bool {
-    status.is_wt_renamed() || status.is_index_renamed()
-}
-
-fn is_modified(status: Status) -> bool {
-    status.is_wt_modified()
-}
-
-fn is_staged(status: Status) -> bool {
-    status.is_index_modified() || status.is_index_new()
-}
-
-fn is_untracked(status: Status) -> bool {
-    status.is_wt_new()
-}
-
-fn stashed_count(repository: &mut Repository) -> Result<usize, git2::Error> {
-    let mut count = 0;
-    repository.stash_foreach(|_, _, _| {
-        count += 1;
-        true
-    })?;
-    Result::Ok(count)
-}
-
-/// Compares the current branch with the branch it is tracking to determine how
-/// far ahead or behind it is in relation
-fn get_ahead_behind(
-    repository: &Repository,
-    branch_name: &str,
-) -> Result<(usize, usize), git2::Error> {
-    let branch_object = repository.revparse_single(branch_name)?;
-    let tracking_branch_name = format!("{}@{{upstream}}", branch_name);
-    let tracking_object = repository.revparse_single(&tracking_branch_name)?;
-
-    let branch_oid = branch_object.id();
-    let tracking_oid = tracking_object.id();
-
-    repository.graph_ahead_behind(branch_oid, tracking_oid)
-}
-
-#[derive(Default, Debug, Copy, Clone)]
-struct RepoStatus {
-    conflicted: usize,
-    deleted: usize,
-    renamed: usize,
-    modified: usize,
-    staged: usize,
-    untracked: usize,
-    stashed: usize,
-}
@@ -0,0 +1,105 @@
+use super::{Context, Module, RootModuleConfig, SegmentConfig};
+
+use crate::configs::git_user::GitUserConfig;
+
+/// Creates a module with the git identity used to commit in the current directory
+///
+/// Will display the effective `user.email`, or the alias of the most specific
+/// pattern it matches, and optionally `user.name`. When no email is configured, it shows a
+/// warning instead.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("git_user");
+    let config = GitUserConfig::try_load(module.config);
+
+    let repo = context.get_repo().ok()?;
+    let (name, email) = {
+        let repository = repo.repository()?;
+        // The config of a repository includes the global and system configs
+        let git_config = repository.config().ok()?.snapshot().ok()?;
+        let name = git_config.get_string("user.name").ok();
+        let email = git_config.get_string("user.email").ok();
+        (name, email)
+    };
+
+    module.set_style(config.style);
+    module.get_prefix().set_value(config.prefix);
+
+    let email = match email.filter(|email| !email.is_empty()) {
+        Some(email) => email,
+        None => {
+            module.create_segment(
+                "no_identity",
+                &config
+                    .no_identity
+                    .with_style(config.no_identity.style.or(Some(config.warning_style))),
+            );
+            return Some(module);
+        }
+    };
+
+    if let Some(name) = name.filter(|_| config.show_name) {
+        module.create_segment("name", &SegmentConfig::new(&name));
+        module.create_segment("separator", &SegmentConfig::new(" "));
+    }
+
+    // The longest pattern is the most specific one
+    let alias = config
+        .aliases
+        .iter()
+        .filter(|(pattern, _)| matches_pattern(pattern, &email))
+        .max_by_key(|(pattern, _)| pattern.len())
+        .map(|(_, alias)| alias);
+    match alias {
+        Some(alias) => module.create_segment("email", alias),
+        None => module.create_segment("email", &SegmentConfig::new(&email)),
+    };
+
+    Some(module)
+}
+
+/// Matches an email with a pattern where `*` matches any text, ignoring case
+fn matches_pattern(pattern: &str, email: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let email = email.to_lowercase();
+    let parts: Vec<&str> = pattern.split('*').collect();
+
+    let (first, last) = match (parts.first(), parts.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return false,
+    };
+    if parts.len() == 1 {
+        return email == pattern;
+    }
+    if !email.starts_with(first) || !email[first.len()..].ends_with(last) {
+        return false;
+    }
+
+    // The parts between the `*` must appear in order between the first and last
+    let mut rest = &email[first.len()..email.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("*@corp.com", "jane@corp.com"));
+        assert!(matches_pattern("*@CORP.com", "Jane@corp.COM"));
+        assert!(matches_pattern("jane@corp.com", "jane@corp.com"));
+        assert!(matches_pattern("*", "jane@corp.com"));
+        assert!(matches_pattern("jane*@*.com", "jane.doe@corp.com"));
+        assert!(!matches_pattern("*@corp.com", "jane@example.com"));
+        assert!(!matches_pattern("*@corp.com", "jane@corp.com.evil"));
+        assert!(!matches_pattern("jane@corp.com", "john@corp.com"));
+        // The first and last parts can't overlap
+        assert!(!matches_pattern("ab*ba", "aba"));
+    }
+}
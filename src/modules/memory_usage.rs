@@ -1,7 +1,7 @@
 use byte_unit::{Byte, ByteUnit};
 use sysinfo::{RefreshKind, SystemExt};
 
-use super::{Context, Module, RootModuleConfig, Shell};
+use super::{Context, Module, RootModuleConfig};
 
 use crate::configs::memory_usage::MemoryConfig;
 
@@ -17,13 +17,6 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("memory_usage");
     let config = MemoryConfig::try_load(module.config);
 
-    // TODO: Update when v1.0 printing refactor is implemented to only
-    // print escapes in a prompt context.
-    let percent_sign = match context.shell {
-        Shell::Zsh => "%%", // % is an escape in zsh, see PROMPT in `man zshmisc`
-        _ => "%",
-    };
-
     if config.disabled {
         return None;
     }
@@ -47,7 +40,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let show_percentage = config.show_percentage;
 
     let ram = if show_percentage {
-        format!("{:.0}{}", percent_mem_used, percent_sign)
+        format!("{:.0}%", percent_mem_used)
     } else {
         format!(
             "{}/{}",
@@ -64,7 +57,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         let percent_swap_used = (used_swap_kib as f64 / total_swap_kib as f64) * 100.;
 
         let swap = if show_percentage {
-            format!("{:.0}{}", percent_swap_used, percent_sign)
+            format!("{:.0}%", percent_swap_used)
         } else {
             format!(
                 "{}/{}",
@@ -14,8 +14,12 @@ mod env_var;
 mod erlang;
 mod git_branch;
 mod git_commit;
+mod git_last_commit;
+mod git_metrics;
+mod git_remote;
 mod git_state;
 mod git_status;
+mod git_user;
 mod golang;
 mod haskell;
 mod hg_branch;
@@ -37,6 +41,7 @@ mod python;
 mod ruby;
 mod rust;
 mod singularity;
+mod status;
 mod terraform;
 mod time;
 mod username;
@@ -50,6 +55,8 @@ use crate::config::{RootModuleConfig, SegmentConfig};
 use crate::context::{Context, Shell};
 use crate::module::Module;
 
+pub use character::module_for_keymap as character_for_keymap;
+
 pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
     match module {
         // Keep these ordered alphabetically.
@@ -69,8 +76,12 @@ pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
         "env_var" => env_var::module(context),
         "git_branch" => git_branch::module(context),
         "git_commit" => git_commit::module(context),
+        "git_last_commit" => git_last_commit::module(context),
+        "git_metrics" => git_metrics::module(context),
+        "git_remote" => git_remote::module(context),
         "git_state" => git_state::module(context),
         "git_status" => git_status::module(context),
+        "git_user" => git_user::module(context),
         "golang" => golang::module(context),
         "haskell" => haskell::module(context),
         "hg_branch" => hg_branch::module(context),
@@ -92,6 +103,7 @@ pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
         "ruby" => ruby::module(context),
         "rust" => rust::module(context),
         "singularity" => singularity::module(context),
+        "status" => status::module(context),
         "terraform" => terraform::module(context),
         "time" => time::module(context),
         "crystal" => crystal::module(context),
@@ -121,8 +133,12 @@ pub fn description(module: &str) -> &'static str {
         "erlang" => "Current OTP version",
         "git_branch" => "The active branch of the repo in your current directory",
         "git_commit" => "The active commit of the repo in your current directory",
+        "git_last_commit" => "How long ago the last commit of the repo was made",
+        "git_metrics" => "The number of lines added and deleted in the repo",
+        "git_remote" => "The forge and repository the current branch is pushed to",
         "git_state" => "The current git operation, and it's progress",
         "git_status" => "Symbol representing the state of the repo",
+        "git_user" => "The git identity used to commit in the repo",
         "golang" => "The currently installed version of Golang",
         "haskell" => "The currently used version of Haskell",
         "hg_branch" => "The active branch of the repo in your current directory",
@@ -143,6 +159,7 @@ pub fn description(module: &str) -> &'static str {
         "python" => "The currently installed version of Python",
         "ruby" => "The currently installed version of Ruby",
         "rust" => "The currently installed version of Rust",
+        "status" => "The exit code of the last command",
         "terraform" => "The currently selected terraform workspace and version",
         "time" => "The current local time",
         "username" => "The active user's username",
@@ -0,0 +1,97 @@
+use super::{Context, Module, RootModuleConfig};
+
+use crate::config::SegmentConfig;
+use crate::configs::status::StatusConfig;
+
+/// Creates a module with the exit code of the last command
+///
+/// Codes above 128 are shown with the name of the signal which killed the
+/// command, and the codes shells use for commands which could not be run are
+/// shown with their meaning. The codes of every command in a pipeline are shown
+/// when one of them failed.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("status");
+    let config: StatusConfig = StatusConfig::try_load(module.config);
+    if config.disabled {
+        return None;
+    }
+
+    let exit_code = context.properties.get("status_code")?.trim();
+    let pipestatus = context
+        .pipestatus()
+        .filter(|pipestatus| config.pipestatus && pipestatus.len() > 1);
+    // A pipeline is shown like the last of its commands which failed
+    let failed_code = pipestatus
+        .as_ref()
+        .and_then(|pipestatus| pipestatus.iter().rev().find(|code| **code != 0));
+    let code = match failed_code {
+        Some(code) => Some(i64::from(*code)),
+        None if exit_code.is_empty() => return None,
+        None => exit_code.parse::<i64>().ok(),
+    };
+    if code == Some(0) && !config.show_on_success {
+        return None;
+    }
+
+    let (symbol, meaning) = match code {
+        Some(0) => (&config.success_symbol, None),
+        Some(126) => (&config.not_executable_symbol, Some("not executable")),
+        Some(127) => (&config.not_found_symbol, Some("not found")),
+        Some(code) => match code.checked_sub(128).and_then(signal_name) {
+            Some(signal) => (&config.signal_symbol, Some(signal)),
+            None => (&config.symbol, None),
+        },
+        None => (&config.symbol, None),
+    };
+
+    module.set_style(symbol.style.unwrap_or(config.style));
+    module.get_prefix().set_value("");
+    module.create_segment("symbol", symbol);
+    let codes = match pipestatus {
+        Some(pipestatus) => pipestatus
+            .iter()
+            .map(i32::to_string)
+            .collect::<Vec<_>>()
+            .join(config.pipestatus_separator),
+        None => exit_code.to_string(),
+    };
+    module.create_segment("code", &SegmentConfig::new(&codes));
+    if let (true, Some(meaning)) = (config.show_meaning, meaning) {
+        module.create_segment("meaning", &SegmentConfig::new(&format!(" {}", meaning)));
+    }
+
+    Some(module)
+}
+
+/// The name of a signal, for the signals which have the same number on all
+/// Unix-like systems
+fn signal_name(signal: i64) -> Option<&'static str> {
+    match signal {
+        1 => Some("SIGHUP"),
+        2 => Some("SIGINT"),
+        3 => Some("SIGQUIT"),
+        4 => Some("SIGILL"),
+        5 => Some("SIGTRAP"),
+        6 => Some("SIGABRT"),
+        8 => Some("SIGFPE"),
+        9 => Some("SIGKILL"),
+        11 => Some("SIGSEGV"),
+        13 => Some("SIGPIPE"),
+        14 => Some("SIGALRM"),
+        15 => Some("SIGTERM"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signal_name() {
+        assert_eq!(signal_name(2), Some("SIGINT"));
+        assert_eq!(signal_name(11), Some("SIGSEGV"));
+        assert_eq!(signal_name(7), None);
+        assert_eq!(signal_name(-1), None);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod directory;
+pub mod forge;
 pub mod java_version_parser;
 
 #[cfg(test)]
@@ -1,26 +1,109 @@
 use ansi_term::ANSIStrings;
 use clap::ArgMatches;
 use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::env;
 use std::fmt::{self, Debug, Write as FmtWrite};
+use std::fs;
 use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use unicode_width::UnicodeWidthChar;
 
+use crate::config::RootModuleConfig;
+use crate::configs::title::TitleConfig;
 use crate::context::{Context, Shell};
+use crate::formatter::StringFormatter;
 use crate::module::Module;
 use crate::module::ALL_MODULES;
 use crate::modules;
+use crate::segment::Segment;
+use crate::utils::{self, Trace};
+
+/// Exit status of `starship prompt --async=fast` when slow modules were replaced
+/// by placeholders, telling the init script to start `--async=full` in the
+/// background and redraw the prompt once it is done (EX_TEMPFAIL).
+pub const ASYNC_PENDING_STATUS: i32 = 75;
 
 pub fn prompt(args: ArgMatches) {
     let context = Context::new(args);
+    let deferred = get_deferred_modules(&context);
+
     let stdout = io::stdout();
     let mut handle = stdout.lock();
-    write!(handle, "{}", get_prompt(context)).unwrap();
+    write!(handle, "{}", get_prompt(&context, &deferred)).unwrap();
+
+    if !deferred.is_empty() {
+        handle.flush().unwrap();
+        process::exit(ASYNC_PENDING_STATUS);
+    }
 }
 
-pub fn get_prompt(context: Context) -> String {
+pub fn get_prompt(context: &Context, deferred: &HashSet<String>) -> String {
+    let modules = compute_modules(context, deferred);
+
+    // fish sets the title itself, from the `fish_title` function in the init script
+    let title = match context.shell {
+        Shell::Fish => None,
+        _ => get_title(context, Some(&modules), deferred, None),
+    };
+
+    let mut buf = render_prompt(context, title.as_deref(), modules.iter());
+
+    // Readline can't redraw the prompt when the vi mode changes, so bash asks for
+    // the prompt in both modes at once. The command mode one follows a record
+    // separator, and only differs in the character module.
+    if context.shell == Shell::Bash
+        && context.properties.get("keymap").map(String::as_str) == Some("vi")
+    {
+        let vicmd = modules::character_for_keymap(context, "vi-command");
+        let modules = modules.iter().map(|module| match &vicmd {
+            Some(vicmd) if module.get_name() == "character" => vicmd,
+            _ => module,
+        });
+        buf.push('\x1e');
+        buf.push_str(&render_prompt(context, title.as_deref(), modules));
+    }
+
+    buf
+}
+
+fn render_prompt<'a, 'b: 'a>(
+    context: &Context,
+    title: Option<&str>,
+    modules: impl Iterator<Item = &'a Module<'b>>,
+) -> String {
     let config = context.config.get_root_config();
     let mut buf = String::new();
 
+    if config.shell_integration {
+        // The previous command has finished, unless this is the background render
+        // of a prompt which has already been drawn
+        if get_async_phase(context) != Some(AsyncPhase::Full) {
+            let status = context
+                .properties
+                .get("status_code")
+                .map_or("0", String::as_str);
+            buf.push_str(&osc_sequence(
+                &format!("133;D;{}", status),
+                context.shell,
+                context.promptvars,
+            ));
+        }
+        buf.push_str(&osc_sequence("133;A", context.shell, context.promptvars));
+        buf.push_str(&osc_sequence(
+            &cwd_report(context),
+            context.shell,
+            context.promptvars,
+        ));
+    }
+
+    if let Some(title) = title {
+        buf.push_str(&title_sequence(title, context.shell, context.promptvars));
+    }
+
     // Write a new line before the prompt
     if config.add_newline {
         writeln!(buf).unwrap();
@@ -32,27 +115,133 @@ pub fn get_prompt(context: Context) -> String {
         buf.push_str("\x1b[J"); // An ASCII control code to clear screen
     }
 
-    let modules = compute_modules(&context);
-
     let mut print_without_prefix = true;
-    let printable = modules.iter();
 
-    for module in printable {
+    for module in modules {
         // Skip printing the prefix of a module after the line_break
         if print_without_prefix {
-            let module_without_prefix = module.to_string_without_prefix(context.shell);
+            let module_without_prefix =
+                module.to_string_without_prefix(context.shell, context.promptvars);
             write!(buf, "{}", module_without_prefix).unwrap()
         } else {
-            let module = module.ansi_strings_for_shell(context.shell);
+            let module = module.ansi_strings_for_prompt(context.shell, context.promptvars);
             write!(buf, "{}", ANSIStrings(&module)).unwrap();
         }
 
         print_without_prefix = module.get_name() == "line_break"
     }
 
+    // The command line starts after the prompt. Its output is marked by the init
+    // script, once the command starts.
+    if config.shell_integration {
+        buf.push_str(&osc_sequence("133;B", context.shell, context.promptvars));
+    }
+
+    if let Shell::Tcsh = context.shell {
+        buf = utils::escape_newlines_for_tcsh(&buf);
+    }
+
     buf
 }
 
+/// Prints the terminal title. Called by the init scripts before a command is run,
+/// with the command in `--command`, to show it while it is running.
+pub fn title(args: ArgMatches) {
+    let plain = args.is_present("plain");
+    let context = Context::new(args);
+    let command = context
+        .properties
+        .get("command")
+        .map(String::as_str)
+        .filter(|command| !command.is_empty());
+
+    if let Some(title) = get_title(&context, None, &HashSet::new(), command) {
+        if plain {
+            print!("{}", title);
+        } else {
+            // Printed straight to the terminal, not as part of the prompt
+            print!("{}", title_sequence(&title, Shell::Unknown, false));
+        }
+    }
+}
+
+/// Renders `format` from the `[title]` config, or `preexec_format` while `command`
+/// is running. Variables are replaced by the text of the module with that name,
+/// taken from the `computed` modules of the prompt if it is part of it.
+fn get_title(
+    context: &Context,
+    computed: Option<&[Module]>,
+    deferred: &HashSet<String>,
+    command: Option<&str>,
+) -> Option<String> {
+    let config = TitleConfig::try_load(context.config.get_module_config("title"));
+    if config.disabled {
+        return None;
+    }
+
+    let format = match command {
+        Some(_) => config.preexec_format,
+        None => config.format,
+    };
+    let formatter = StringFormatter::new(format)
+        .map_err(|err| log::warn!("Unable to parse the title format: {}", err))
+        .ok()?;
+
+    let in_prompt = match computed {
+        Some(_) => get_prompt_order(context).iter().map(Mod::name).collect(),
+        None => HashSet::new(),
+    };
+    let segments = formatter
+        .map(|variable| match variable {
+            "command" => command.map(String::from),
+            name if deferred.contains(name) => None,
+            // Modules which weren't shown in the prompt aren't in `computed`
+            name if in_prompt.contains(name) => computed?
+                .iter()
+                .find(|module| module.get_name() == name)
+                .map(|module| module.get_segments().concat()),
+            name if ALL_MODULES.contains(&name) => {
+                modules::handle(name, context).map(|module| module.get_segments().concat())
+            }
+            name => {
+                log::warn!("Unknown variable in the title format: {}", name);
+                None
+            }
+        })
+        .parse(None);
+
+    // Control characters would end the escape sequence early
+    let title = segments
+        .iter()
+        .map(Segment::get_value)
+        .collect::<String>()
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect::<String>();
+
+    Some(title.trim().to_string())
+}
+
+/// Wraps the title in the OSC 0 sequence, which sets both the window and tab
+/// (icon) title.
+fn title_sequence(title: &str, shell: Shell, promptvars: bool) -> String {
+    osc_sequence(&format!("0;{}", title), shell, promptvars)
+}
+
+/// Reports the current directory to the terminal with OSC 7, as a `file://` URI,
+/// so new tabs and windows can be opened in it.
+fn cwd_report(context: &Context) -> String {
+    format!("7;{}", utils::file_uri(&context.current_dir))
+}
+
+/// Wraps `text` in an OSC (operating system command) sequence, and in the escapes
+/// `shell` needs to ignore it in the prompt. The text still has to be escaped
+/// within those escapes.
+fn osc_sequence(text: &str, shell: Shell, promptvars: bool) -> String {
+    let text = utils::escape_for_shell(text, shell, promptvars);
+    utils::wrap_seq_for_shell(format!("\x1b]{}\x07", text), shell, '\x1b', '\x07')
+}
+
 pub fn module(module_name: &str, args: ArgMatches) {
     let context = Context::new(args);
     let module = get_module(module_name, context).unwrap_or_default();
@@ -60,7 +249,10 @@ pub fn module(module_name: &str, args: ArgMatches) {
 }
 
 pub fn get_module(module_name: &str, context: Context) -> Option<String> {
-    modules::handle(module_name, &context).map(|m| m.to_string())
+    modules::handle(module_name, &context).map(|module| {
+        let ansi_strings = module.ansi_strings_for_shell(context.shell, context.promptvars);
+        ANSIStrings(&ansi_strings).to_string()
+    })
 }
 
 pub fn explain(args: ArgMatches) {
@@ -74,7 +266,7 @@ pub fn explain(args: ArgMatches) {
 
     let dont_print = vec!["line_break", "character"];
 
-    let modules = compute_modules(&context)
+    let modules = compute_modules(&context, &HashSet::new())
         .into_iter()
         .filter(|module| !dont_print.contains(&module.get_name().as_str()))
         .map(|module| {
@@ -131,12 +323,141 @@ pub fn explain(args: ArgMatches) {
     }
 }
 
-fn compute_modules<'a>(context: &'a Context) -> Vec<Module<'a>> {
-    enum Mod<'a> {
-        Builtin(&'a str),
-        Custom(&'a str),
+pub fn timings(args: ArgMatches) {
+    let context = Context::new(args);
+
+    struct ModuleTiming {
+        name: String,
+        value: String,
+        duration: Duration,
+        trace: Trace,
+    }
+
+    let start = Instant::now();
+    let mut timings = get_prompt_order(&context)
+        .par_iter()
+        .map(|module| {
+            let start = Instant::now();
+            let (computed, trace) = utils::with_trace(|| handle_module(module, &context));
+            let duration = start.elapsed();
+
+            let value = computed
+                .filter(|module| module.get_name() != "line_break")
+                .map(|module| {
+                    let ansi_strings = module.ansi_strings();
+                    ANSIStrings(&ansi_strings[1..ansi_strings.len() - 1]).to_string()
+                })
+                .unwrap_or_default();
+
+            ModuleTiming {
+                name: module.name(),
+                value,
+                duration,
+                trace,
+            }
+        })
+        .collect::<Vec<ModuleTiming>>();
+    let wall_time = start.elapsed();
+
+    timings.sort_by_key(|timing| Reverse(timing.duration));
+
+    let total = timings
+        .iter()
+        .map(|timing| timing.duration)
+        .sum::<Duration>();
+    let name_width = timings
+        .iter()
+        .map(|timing| timing.name.len())
+        .max()
+        .unwrap_or(0);
+
+    println!("\n Here are the timings of modules in your prompt (slowest first):");
+    for timing in &timings {
+        println!(
+            " {:name_width$}  {:>10}  -  {}",
+            timing.name,
+            format_duration(timing.duration),
+            timing.value,
+            name_width = name_width
+        );
+
+        for (resource, duration) in &timing.trace.resources {
+            println!(
+                " {:name_width$}  {:>10}     triggered {}",
+                "",
+                format_duration(*duration),
+                resource,
+                name_width = name_width
+            );
+        }
+
+        for (command, duration) in &timing.trace.commands {
+            println!(
+                " {:name_width$}  {:>10}     ran `{}`",
+                "",
+                format_duration(*duration),
+                command,
+                name_width = name_width
+            );
+        }
     }
 
+    println!(
+        "\n Total: {} ({} elapsed, since modules are computed in parallel)",
+        format_duration(total),
+        format_duration(wall_time)
+    );
+}
+
+enum Mod<'a> {
+    Builtin(&'a str),
+    Custom(&'a str),
+}
+
+impl Mod<'_> {
+    fn name(&self) -> String {
+        match self {
+            Mod::Builtin(builtin) => builtin.to_string(),
+            Mod::Custom(custom) => format!("custom.{}", custom),
+        }
+    }
+}
+
+fn compute_modules<'a>(context: &'a Context, deferred: &HashSet<String>) -> Vec<Module<'a>> {
+    let computed = get_prompt_order(context)
+        .par_iter()
+        .map(|module| {
+            let name = module.name();
+            if deferred.contains(&name) {
+                let placeholder = async_placeholder(&name, context);
+                return (name, placeholder, None);
+            }
+
+            // Compute segments
+            let start = Instant::now();
+            let computed = handle_module(module, context);
+            (name, computed, Some(start.elapsed()))
+        })
+        .collect::<Vec<(String, Option<Module<'a>>, Option<Duration>)>>();
+
+    if get_async_phase(context) == Some(AsyncPhase::Full) {
+        update_slow_modules(context, &computed);
+    }
+
+    computed
+        .into_iter()
+        .filter_map(|(_, module, _)| module) // Remove segments set to `None`
+        .collect::<Vec<Module<'a>>>()
+}
+
+fn handle_module<'a>(module: &Mod<'a>, context: &'a Context) -> Option<Module<'a>> {
+    match module {
+        Mod::Builtin(builtin) => modules::handle(builtin, context),
+        Mod::Custom(custom) => modules::custom::module(custom, context),
+    }
+}
+
+fn get_prompt_order<'a>(context: &'a Context) -> Vec<Mod<'a>> {
     struct DebugCustomModules<'tmp>(&'tmp toml::value::Table);
 
     impl Debug for DebugCustomModules<'_> {
@@ -196,13 +517,183 @@ fn compute_modules<'a>(context: &'a Context) -> Vec<Module<'a>> {
     }
 
     prompt_order
-        .par_iter()
-        .map(|module| match module {
-            Mod::Builtin(builtin) => modules::handle(builtin, context),
-            Mod::Custom(custom) => modules::custom::module(custom, context),
-        }) // Compute segments
-        .flatten() // Remove segments set to `None`
-        .collect::<Vec<Module<'a>>>()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AsyncPhase {
+    /// Render placeholders in place of slow modules
+    Fast,
+    /// Render every module, measuring which ones are slow
+    Full,
+}
+
+fn get_async_phase(context: &Context) -> Option<AsyncPhase> {
+    match context.properties.get("async").map(String::as_str) {
+        Some("fast") => Some(AsyncPhase::Fast),
+        Some("full") => Some(AsyncPhase::Full),
+        _ => None,
+    }
+}
+
+/// Returns the names of the modules in the prompt which should be left out of
+/// the first, fast render: those listed in `async_modules`, and those which
+/// took longer than `async_threshold` during the previous full render.
+fn get_deferred_modules(context: &Context) -> HashSet<String> {
+    let config = context.config.get_root_config();
+    if !config.async_render || get_async_phase(context) != Some(AsyncPhase::Fast) {
+        return HashSet::new();
+    }
+
+    let measured = read_slow_modules()
+        .remove(&slow_modules_key(context))
+        .map(|(_, modules)| modules)
+        .unwrap_or_default();
+    get_prompt_order(context)
+        .iter()
+        .map(Mod::name)
+        .filter(|name| config.async_modules.contains(&name.as_str()) || measured.contains(name))
+        .filter(|name| can_apply(name, context))
+        .collect()
+}
+
+/// Whether a module could show anything at all, so that it isn't replaced by a
+/// placeholder, and rendered in the background, where it never would. Git
+/// modules only apply within a repository.
+fn can_apply(name: &str, context: &Context) -> bool {
+    if name.starts_with("git_") {
+        return context
+            .get_repo()
+            .ok()
+            .and_then(|repo| repo.root.as_ref())
+            .is_some();
+    }
+    true
+}
+
+fn async_placeholder<'a>(name: &str, context: &'a Context) -> Option<Module<'a>> {
+    let config = context.config.get_root_config();
+    let mut module = context.new_module(name);
+    module.get_prefix().set_value("");
+    module.create_segment("async_placeholder", &config.async_placeholder);
+    Some(module)
+}
+
+/// How long the slow modules of a directory are remembered, once it is no longer
+/// rendered in the background
+const SLOW_MODULES_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The file remembering which modules were slow during the last full render
+fn slow_modules_path() -> Option<PathBuf> {
+    env::var("STARSHIP_CACHE")
+        .map(PathBuf::from)
+        .ok()
+        .or_else(|| dirs::cache_dir().map(|dir| dir.join("starship")))
+        .map(|dir| dir.join("slow_modules"))
+}
+
+/// Modules are slow in some repositories and not in others, so they are
+/// remembered for the root of the repository, or for `current_dir` outside one
+fn slow_modules_key(context: &Context) -> String {
+    let repo_root = context.get_repo().ok().and_then(|repo| repo.root.as_ref());
+    let dir = repo_root.unwrap_or(&context.current_dir);
+    dir.to_string_lossy().replace(&['\t', '\n'][..], " ")
+}
+
+/// The slow modules of each directory, and when they were measured in seconds
+/// since the epoch. Each line of the file is a directory, its timestamp and
+/// its modules, separated by tabs.
+type SlowModules = HashMap<String, (u64, Vec<String>)>;
+
+fn read_slow_modules() -> SlowModules {
+    let now = seconds_since_epoch(SystemTime::now());
+    let contents = slow_modules_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .unwrap_or_default();
+
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let key = fields.next()?.to_string();
+            let measured = fields.next()?.parse::<u64>().ok()?;
+            let modules = fields.map(String::from).collect();
+            Some((key, (measured, modules)))
+        })
+        .filter(|(_, (measured, _))| now.saturating_sub(*measured) < SLOW_MODULES_TTL.as_secs())
+        .collect()
+}
+
+fn update_slow_modules(context: &Context, computed: &[(String, Option<Module>, Option<Duration>)]) {
+    let config = context.config.get_root_config();
+    let threshold = Duration::from_millis(config.async_threshold);
+
+    let mut slow_modules = computed
+        .iter()
+        .filter_map(|(name, _, duration)| match duration {
+            Some(duration) if *duration >= threshold => Some(name.clone()),
+            _ => None,
+        })
+        .collect::<Vec<String>>();
+    slow_modules.sort();
+
+    let now = seconds_since_epoch(SystemTime::now());
+    let key = slow_modules_key(context);
+    let mut all_slow_modules = read_slow_modules();
+    // Rewrite an unchanged entry only once it is halfway to expiring
+    let is_fresh = |(measured, modules): &(u64, Vec<String>)| {
+        *modules == slow_modules && now.saturating_sub(*measured) < SLOW_MODULES_TTL.as_secs() / 2
+    };
+    match all_slow_modules.get(&key) {
+        Some(entry) if is_fresh(entry) => return,
+        None if slow_modules.is_empty() => return,
+        _ => {}
+    }
+
+    let path = match slow_modules_path() {
+        Some(path) => path,
+        None => return,
+    };
+    log::debug!(
+        "Slow modules of {:?} changed, writing {:?} to {:?}",
+        key,
+        slow_modules,
+        path
+    );
+
+    if slow_modules.is_empty() {
+        all_slow_modules.remove(&key);
+    } else {
+        all_slow_modules.insert(key, (now, slow_modules));
+    }
+    let contents = all_slow_modules
+        .iter()
+        .map(|(key, (measured, modules))| {
+            let mut fields = vec![key.clone(), measured.to_string()];
+            fields.extend(modules.iter().cloned());
+            fields.join("\t") + "\n"
+        })
+        .collect::<String>();
+
+    // Several shells can write the file at once, so each writes its own file and
+    // replaces the old one with it, rather than writing over it in place
+    let temp_path = path.with_extension(format!("{}.tmp", process::id()));
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&temp_path, contents))
+        .and_then(|_| fs::rename(&temp_path, &path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    if let Err(error) = result {
+        log::debug!("Unable to write slow modules to {:?}: {}", path, error);
+    }
+}
+
+fn seconds_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
 }
 
 fn should_add_implicit_custom_module(
@@ -228,6 +719,10 @@ fn should_add_implicit_custom_module(
         .unwrap_or(false)
 }
 
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
+
 fn count_wide_chars(value: &str) -> usize {
     value.chars().filter(|c| c.width().unwrap_or(0) > 1).count()
 }
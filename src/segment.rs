@@ -1,6 +1,9 @@
 use ansi_term::{ANSIString, Style};
 use std::fmt;
 
+use crate::context::Shell;
+use crate::utils::escape_for_shell;
+
 /// A segment is a single configurable element in a module. This will usually
 /// contain a data point to provide context for the prompt's user
 /// (e.g. The version that software is running).
@@ -14,6 +17,9 @@ pub struct Segment {
 
     /// The string value of the current segment.
     pub value: String,
+
+    /// The URL the segment links to, if the terminal supports hyperlinks.
+    pub hyperlink: Option<String>,
 }
 
 impl Segment {
@@ -23,6 +29,7 @@ impl Segment {
             _name: name.to_string(),
             style: None,
             value: "".to_string(),
+            hyperlink: None,
         }
     }
 
@@ -51,16 +58,39 @@ impl Segment {
         self
     }
 
+    /// Makes the segment a hyperlink to `url`.
+    pub fn set_hyperlink<T>(&mut self, url: T) -> &mut Self
+    where
+        T: Into<String>,
+    {
+        self.hyperlink = Some(url.into());
+        self
+    }
+
     /// Gets the value of the segment.
     pub fn get_value(&self) -> &str {
         &self.value
     }
 
-    // Returns the ANSIString of the segment value, not including its prefix and suffix
+    // Returns the ANSIString of the segment value, not including its prefix and suffix.
     pub fn ansi_string(&self) -> ANSIString {
+        self.ansi_string_for_shell(Shell::Unknown, true)
+    }
+
+    // Returns the ANSIString of the segment value, with the value escaped so that
+    // `shell` shows it as it is.
+    // A hyperlink is wrapped around the value with OSC 8, ended by BEL rather than
+    // ESC \, as bash would read that backslash as an escape in the prompt.
+    pub fn ansi_string_for_shell(&self, shell: Shell, promptvars: bool) -> ANSIString<'static> {
+        let value = escape_for_shell(&self.value, shell, promptvars);
+        let value = match &self.hyperlink {
+            Some(url) => format!("\x1b]8;;{}\x07{}\x1b]8;;\x07", url, value),
+            None => value,
+        };
+
         match self.style {
-            Some(style) => style.paint(&self.value),
-            None => ANSIString::from(&self.value),
+            Some(style) => style.paint(value),
+            None => ANSIString::from(value),
         }
     }
 
@@ -1,7 +1,10 @@
+use std::cell::RefCell;
 use std::fs::File;
 use std::io::{Read, Result};
-use std::path::Path;
+use std::path::{Component, Path};
 use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use crate::context::Shell;
 
@@ -128,11 +131,165 @@ active boot switches: -d:release\n",
     }
 }
 
-/// Wraps ANSI color escape sequences in the shell-appropriate wrappers.
+/// The external commands run and lazy `Context` resources initialised while
+/// computing a single module, used by `starship timings`.
+#[derive(Debug, Default)]
+pub struct Trace {
+    pub commands: Vec<(String, Duration)>,
+    pub resources: Vec<(&'static str, Duration)>,
+}
+
+/// A trace shared by every thread doing work for the same module
+pub type SharedTrace = Arc<Mutex<Trace>>;
+
+thread_local! {
+    static TRACE: RefCell<Option<SharedTrace>> = RefCell::new(None);
+}
+
+/// Run `f`, collecting everything it records with `trace_command` and
+/// `trace_resource`, including on the threads given the trace by `in_trace`.
+pub fn with_trace<T>(f: impl FnOnce() -> T) -> (T, Trace) {
+    let shared = SharedTrace::default();
+    let result = in_trace(Some(shared.clone()), f);
+    let trace = std::mem::take(&mut *shared.lock().unwrap());
+    (result, trace)
+}
+
+/// The trace being collected on the current thread, to be passed to `in_trace`
+/// on any thread doing work on its behalf
+pub fn current_trace() -> Option<SharedTrace> {
+    TRACE.with(|trace| trace.borrow().clone())
+}
+
+/// Run `f`, recording into `trace` rather than the current thread's trace
+pub fn in_trace<T>(trace: Option<SharedTrace>, f: impl FnOnce() -> T) -> T {
+    // Keep the outer trace around, in case rayon runs another module on this
+    // thread while the current one is waiting on a nested parallel iterator
+    let outer = TRACE.with(|current| current.replace(trace));
+    let result = f();
+    TRACE.with(|current| current.replace(outer));
+    result
+}
+
+/// Record how long an external command took, if a trace is being collected
+pub fn trace_command(cmd: &str, args: &[&str], duration: Duration) {
+    if let Some(trace) = current_trace() {
+        let command = match args.len() {
+            0 => String::from(cmd),
+            _ => format!("{} {}", cmd, args.join(" ")),
+        };
+        trace.lock().unwrap().commands.push((command, duration));
+    }
+}
+
+/// Record that a lazily computed `Context` resource was initialised
+pub fn trace_resource(name: &'static str, duration: Duration) {
+    if let Some(trace) = current_trace() {
+        trace.lock().unwrap().resources.push((name, duration));
+    }
+}
+
+/// A `file://` URI for `path` on this host, with each component percent-encoded
+pub fn file_uri(path: &Path) -> String {
+    let host = gethostname::gethostname();
+    let path = path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(urlencoding::encode(&name.to_string_lossy())),
+            Component::Prefix(prefix) => Some(prefix.as_os_str().to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect::<Vec<String>>()
+        .join("/");
+
+    format!("file://{}/{}", host.to_string_lossy(), path)
+}
+
+/// Escapes `text` so that `shell` shows it as it is in the prompt.
+///
+/// bash decodes backslash escapes in `PS1`, and then expands parameters and
+/// commands if its `promptvars` option is on, so `$` and backticks need escaping
+/// which survives the first step. zsh expands them too if its `prompt_subst`
+/// option is on, and `%` sequences after that. xonsh formats its prompt like
+/// `str.format`, so braces are doubled.
+pub fn escape_for_shell(text: &str, shell: Shell, promptvars: bool) -> String {
+    match shell {
+        Shell::Bash if promptvars => text
+            .replace('\\', "\\\\\\\\")
+            .replace('$', "\\\\$")
+            .replace('`', "\\\\`"),
+        Shell::Bash => text.replace('\\', "\\\\"),
+        Shell::Zsh if promptvars => text
+            .replace('\\', "\\\\")
+            .replace('$', "\\$")
+            .replace('`', "\\`")
+            .replace('%', "%%"),
+        Shell::Zsh => text.replace('%', "%%"),
+        Shell::Tcsh => escape_for_tcsh(text.to_string()),
+        Shell::Xonsh => text.replace('{', "{{").replace('}', "}}"),
+        _ => text.to_string(),
+    }
+}
+
+/// tcsh expands `%` sequences and `!` history references in its prompt, so they
+/// have to be escaped in any text which is printed as part of it.
+pub fn escape_for_tcsh(text: String) -> String {
+    text.replace('%', "%%").replace('!', "\\!")
+}
+
+/// tcsh reads the prompt with command substitution, which would split it into
+/// several words at each newline, so tcsh is left to expand `\n` instead.
+pub fn escape_newlines_for_tcsh(prompt: &str) -> String {
+    prompt.replace('\n', " \\n")
+}
+
+/// Wraps ANSI color escape sequences, and OSC sequences such as hyperlinks, in
+/// the shell-appropriate wrappers.
+///
+/// zsh and tcsh expand `%` even within their escapes, so it is escaped inside them.
 pub fn wrap_colorseq_for_shell(ansi: String, shell: Shell) -> String {
-    const ESCAPE_BEGIN: char = '\u{1b}';
-    const ESCAPE_END: char = 'm';
-    wrap_seq_for_shell(ansi, shell, ESCAPE_BEGIN, ESCAPE_END)
+    let (escape_begin, escape_end) = match shell_escapes(shell) {
+        Some(escapes) => escapes,
+        None => return ansi,
+    };
+
+    let mut final_string = String::with_capacity(ansi.len());
+    let mut chars = ansi.chars().peekable();
+    while let Some(x) = chars.next() {
+        if x != '\u{1b}' {
+            final_string.push(x);
+            continue;
+        }
+
+        // An OSC sequence ends with BEL or ST (ESC \\), a color sequence with `m`
+        let is_osc = chars.peek() == Some(&']');
+        let mut sequence = x.to_string();
+        while let Some(x) = chars.next() {
+            sequence.push(x);
+            let is_end = match x {
+                '\u{7}' => is_osc,
+                '\u{1b}' if is_osc && chars.peek() == Some(&'\\') => {
+                    sequence.push(chars.next().unwrap());
+                    true
+                }
+                'm' => !is_osc,
+                _ => false,
+            };
+            if is_end {
+                break;
+            }
+        }
+
+        sequence = match shell {
+            Shell::Zsh => sequence.replace('%', "%%"),
+            Shell::Tcsh => escape_for_tcsh(sequence),
+            _ => sequence,
+        };
+        final_string.push_str(escape_begin);
+        final_string.push_str(&sequence);
+        final_string.push_str(escape_end);
+    }
+    final_string
 }
 
 /// Many shells cannot deal with raw unprintable characters and miscompute the cursor position,
@@ -144,10 +301,10 @@ pub fn wrap_seq_for_shell(
     escape_begin: char,
     escape_end: char,
 ) -> String {
-    const BASH_BEG: &str = "\u{5c}\u{5b}"; // \[
-    const BASH_END: &str = "\u{5c}\u{5d}"; // \]
-    const ZSH_BEG: &str = "\u{25}\u{7b}"; // %{
-    const ZSH_END: &str = "\u{25}\u{7d}"; // %}
+    let (shell_begin, shell_end) = match shell_escapes(shell) {
+        Some(escapes) => escapes,
+        None => return ansi,
+    };
 
     // ANSI escape codes cannot be nested, so we can keep track of whether we're
     // in an escape or not with a single boolean variable
@@ -157,18 +314,10 @@ pub fn wrap_seq_for_shell(
         .map(|x| {
             if x == escape_begin && !escaped {
                 escaped = true;
-                match shell {
-                    Shell::Bash => format!("{}{}", BASH_BEG, escape_begin),
-                    Shell::Zsh => format!("{}{}", ZSH_BEG, escape_begin),
-                    _ => x.to_string(),
-                }
+                format!("{}{}", shell_begin, escape_begin)
             } else if x == escape_end && escaped {
                 escaped = false;
-                match shell {
-                    Shell::Bash => format!("{}{}", escape_end, BASH_END),
-                    Shell::Zsh => format!("{}{}", escape_end, ZSH_END),
-                    _ => x.to_string(),
-                }
+                format!("{}{}", escape_end, shell_end)
             } else {
                 x.to_string()
             }
@@ -177,9 +326,25 @@ pub fn wrap_seq_for_shell(
     final_string
 }
 
+/// The escapes which tell `shell` that the characters between them are unprintable.
+///
+/// Nushell, Elvish and Xonsh parse the escape codes in the prompt themselves
+/// to compute its width, so they don't have any.
+fn shell_escapes(shell: Shell) -> Option<(&'static str, &'static str)> {
+    match shell {
+        Shell::Bash => Some(("\u{5c}\u{5b}", "\u{5c}\u{5d}")), // \[ and \]
+        Shell::Zsh | Shell::Tcsh => Some(("\u{25}\u{7b}", "\u{25}\u{7d}")), // %{ and %}
+        _ => None,
+    }
+}
+
 fn internal_exec_cmd(cmd: &str, args: &[&str]) -> Option<CommandOutput> {
     log::trace!("Executing command {:?} with args {:?}", cmd, args);
-    match Command::new(cmd).args(args).output() {
+    let start = Instant::now();
+    let output = Command::new(cmd).args(args).output();
+    trace_command(cmd, args, start.elapsed());
+
+    match output {
         Ok(output) => {
             let stdout_string = String::from_utf8(output.stdout).unwrap();
             let stderr_string = String::from_utf8(output.stderr).unwrap();
@@ -272,6 +437,89 @@ mod tests {
         assert_eq!(result, expected)
     }
 
+    #[test]
+    fn test_escape_for_tcsh() {
+        assert_eq!(escape_for_tcsh("100%".to_string()), "100%%");
+        assert_eq!(escape_for_tcsh("!1".to_string()), "\\!1");
+        assert_eq!(escape_for_tcsh("main".to_string()), "main");
+    }
+
+    #[test]
+    fn test_escape_newlines_for_tcsh() {
+        assert_eq!(escape_newlines_for_tcsh("\n❯ "), " \\n❯ ");
+        assert_eq!(
+            escape_newlines_for_tcsh("~/src\n%{\x1b[1;32m%}❯%{\x1b[0m%} "),
+            "~/src \\n%{\x1b[1;32m%}❯%{\x1b[0m%} "
+        );
+        assert_eq!(escape_newlines_for_tcsh("main"), "main");
+    }
+
+    #[test]
+    fn test_escape_for_shell() {
+        let text = r"$(rm) `rm` C:\ 100%";
+        assert_eq!(
+            escape_for_shell(text, Shell::Bash, true),
+            r"\\$(rm) \\`rm\\` C:\\\\ 100%"
+        );
+        assert_eq!(
+            escape_for_shell(text, Shell::Bash, false),
+            r"$(rm) `rm` C:\\ 100%"
+        );
+        assert_eq!(
+            escape_for_shell(text, Shell::Zsh, true),
+            r"\$(rm) \`rm\` C:\\ 100%%"
+        );
+        assert_eq!(
+            escape_for_shell(text, Shell::Zsh, false),
+            r"$(rm) `rm` C:\ 100%%"
+        );
+        assert_eq!(
+            escape_for_shell(text, Shell::Tcsh, true),
+            r"$(rm) `rm` C:\ 100%%"
+        );
+        assert_eq!(escape_for_shell("!!", Shell::Tcsh, true), r"\!\!");
+        assert_eq!(
+            escape_for_shell("{user} ${HOME}", Shell::Xonsh, true),
+            "{{user}} ${{HOME}}"
+        );
+        assert_eq!(escape_for_shell(text, Shell::Fish, true), text);
+    }
+
+    #[test]
+    fn test_hyperlink_wrappers() {
+        let link = "\x1b[1;36m\x1b]8;;file://host/a%20b\x07mmm\x1b]8;;\x07\x1b[0m";
+
+        let bresult = wrap_colorseq_for_shell(link.to_string(), Shell::Bash);
+        assert_eq!(
+            bresult,
+            "\\[\x1b[1;36m\\]\\[\x1b]8;;file://host/a%20b\x07\\]mmm\\[\x1b]8;;\x07\\]\\[\x1b[0m\\]"
+        );
+
+        let zresult = wrap_colorseq_for_shell(link.to_string(), Shell::Zsh);
+        assert_eq!(
+            zresult,
+            "%{\x1b[1;36m%}%{\x1b]8;;file://host/a%%20b\x07%}mmm%{\x1b]8;;\x07%}%{\x1b[0m%}"
+        );
+
+        let tresult = wrap_colorseq_for_shell(link.to_string(), Shell::Tcsh);
+        assert_eq!(
+            tresult,
+            "%{\x1b[1;36m%}%{\x1b]8;;file://host/a%%20b\x07%}mmm%{\x1b]8;;\x07%}%{\x1b[0m%}"
+        );
+
+        // tcsh also expands `!` history references within its escapes
+        let tresult = wrap_colorseq_for_shell("\x1b]0;a!b\x07".to_string(), Shell::Tcsh);
+        assert_eq!(tresult, "%{\x1b]0;a\\!b\x07%}");
+
+        // The sequence can also be ended by ST
+        let zresult = wrap_colorseq_for_shell("\x1b]8;;\x1b\\mmm".to_string(), Shell::Zsh);
+        assert_eq!(zresult, "%{\x1b]8;;\x1b\\%}mmm");
+
+        let title = "\x1b]0;mmm\x07text";
+        let bresult = wrap_colorseq_for_shell(title.to_string(), Shell::Bash);
+        assert_eq!(bresult, "\\[\x1b]0;mmm\x07\\]text");
+    }
+
     #[test]
     fn test_color_sequence_wrappers() {
         let test0 = "\x1b2mhellomynamekeyes\x1b2m"; // BEGIN: \x1b     END: m
@@ -308,5 +556,13 @@ mod tests {
         assert_eq!(&bresult3, "\\[OH NO\\]");
         assert_eq!(&bresult4, "herpaderp");
         assert_eq!(&bresult5, "");
+
+        let tresult0 = wrap_seq_for_shell(test0.to_string(), Shell::Tcsh, '\x1b', 'm');
+        assert_eq!(&tresult0, "%{\x1b2m%}hellomynamekeyes%{\x1b2m%}");
+
+        for shell in &[Shell::Nu, Shell::Elvish, Shell::Xonsh] {
+            let result0 = wrap_seq_for_shell(test0.to_string(), *shell, '\x1b', 'm');
+            assert_eq!(&result0, test0);
+        }
     }
 }
@@ -103,7 +103,7 @@ fn char_module_zsh_keymap() -> io::Result<()> {
     // zle keymap is other
     let output = common::render_module("character")
         .env("STARSHIP_SHELL", "zsh")
-        .arg("--keymap=visual")
+        .arg("--keymap=main")
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
     assert!(actual.contains(&expected_other));
@@ -141,10 +141,112 @@ fn char_module_fish_keymap() -> io::Result<()> {
     // fish keymap is other
     let output = common::render_module("character")
         .env("STARSHIP_SHELL", "fish")
-        .arg("--keymap=visual")
+        .arg("--keymap=insert")
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
     assert!(actual.contains(&expected_other));
 
     Ok(())
 }
+
+#[test]
+fn char_module_fish_vi_modes() -> io::Result<()> {
+    let modes = [
+        ("replace", Color::Purple.bold().paint("❮")),
+        ("replace_one", Color::Purple.bold().paint("❮")),
+        ("visual", Color::Yellow.bold().paint("❮")),
+    ];
+
+    for (keymap, symbol) in modes.iter() {
+        let output = common::render_module("character")
+            .env("STARSHIP_SHELL", "fish")
+            .arg(format!("--keymap={}", keymap))
+            .output()?;
+        let actual = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(format!("{} ", symbol), actual);
+    }
+
+    // specified replace character
+    let output = common::render_module("character")
+        .use_config(toml::toml! {
+            [character]
+            replace_symbol = { value = "R", style = "bold blue" }
+        })
+        .env("STARSHIP_SHELL", "zsh")
+        .arg("--keymap=replace")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(format!("{} ", Color::Blue.bold().paint("R")), actual);
+
+    Ok(())
+}
+
+#[test]
+fn char_module_bash_keymap() -> io::Result<()> {
+    let output = common::render_module("character")
+        .env("STARSHIP_SHELL", "bash")
+        .arg("--keymap=vi-command")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(actual.contains("❮"));
+
+    let output = common::render_module("character")
+        .env("STARSHIP_SHELL", "bash")
+        .arg("--keymap=vi-insert")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(actual.contains("❯"));
+
+    Ok(())
+}
+
+#[test]
+fn bash_vi_prompt_renders_both_keymaps() -> io::Result<()> {
+    let output = common::render_prompt()
+        .env("STARSHIP_SHELL", "bash")
+        .arg("--keymap=vi")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let (insert, command) = actual.split_at(actual.find('\x1e').unwrap());
+    assert!(insert.contains("❯") && !insert.contains("❮"));
+    assert!(command.contains("❮") && !command.contains("❯"));
+
+    Ok(())
+}
+
+#[test]
+fn char_module_pipestatus() -> io::Result<()> {
+    let output = common::render_module("character")
+        .arg("--status=0")
+        .arg("--pipestatus=1 0")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Green.bold().paint("❯"));
+    assert_eq!(expected, actual);
+
+    let output = common::render_module("character")
+        .use_config(toml::toml! {
+            [character]
+            use_pipestatus = true
+        })
+        .arg("--status=0")
+        .arg("--pipestatus=1 0")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Red.bold().paint("❯"));
+    assert_eq!(expected, actual);
+
+    Ok(())
+}
+
+#[test]
+fn char_module_shell_override() -> io::Result<()> {
+    let output = common::render_module("character")
+        .arg("--shell=fish")
+        .arg("--keymap=default")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(actual.contains("❮"));
+
+    Ok(())
+}
@@ -4,6 +4,7 @@ use std::io::prelude::*;
 use std::io::{Error, ErrorKind};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
 use std::{env, fs, io, process};
 
 static MANIFEST_DIR: Lazy<&'static Path> = Lazy::new(|| Path::new(env!("CARGO_MANIFEST_DIR")));
@@ -43,6 +44,32 @@ pub fn render_module(module_name: &str) -> process::Command {
     command
 }
 
+/// Render the terminal title, as set before a command runs
+pub fn render_title() -> process::Command {
+    let mut command = process::Command::new(EXE_PATH);
+
+    command
+        .arg("title")
+        .env_clear()
+        .env("PATH", env!("PATH")) // Provide the $PATH variable so that external programs are runnable
+        .env("STARSHIP_CONFIG", EMPTY_CONFIG.as_os_str());
+
+    command
+}
+
+/// Render the per-module timings of `starship timings`
+pub fn render_timings() -> process::Command {
+    let mut command = process::Command::new(EXE_PATH);
+
+    command
+        .arg("timings")
+        .env_clear()
+        .env("PATH", env!("PATH")) // Provide the $PATH variable so that external programs are runnable
+        .env("STARSHIP_CONFIG", EMPTY_CONFIG.as_os_str());
+
+    command
+}
+
 /// Create a repo from the fixture to be used in git module tests
 /// Please delete the returned directory manually after usage with `remove_dir_all::remove_dir_all`
 pub fn create_fixture_repo() -> io::Result<PathBuf> {
@@ -79,6 +106,60 @@ pub fn create_fixture_repo() -> io::Result<PathBuf> {
     Ok(repo_path)
 }
 
+/// Create a repo from the fixture with a commit made `seconds_ago`, which adds
+/// `lines.txt` with three lines
+/// Please delete the returned directory manually after usage with `remove_dir_all::remove_dir_all`
+pub fn create_repo_with_commit(seconds_ago: u64) -> io::Result<PathBuf> {
+    let repo_dir = create_fixture_repo()?;
+    fs::write(repo_dir.join("lines.txt"), "a\nb\nc\n")?;
+    git(&repo_dir, &["add", "lines.txt"])?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let date = format!("{} +0000", now - seconds_ago);
+    Command::new("git")
+        .args(&["commit", "-m", "Add lines"])
+        .env("GIT_COMMITTER_DATE", &date)
+        .env("GIT_AUTHOR_DATE", &date)
+        .current_dir(&repo_dir)
+        .output()?;
+
+    Ok(repo_dir)
+}
+
+/// Run git with `args` in `repo_dir`
+pub fn git(repo_dir: &Path, args: &[&str]) -> io::Result<()> {
+    Command::new("git")
+        .args(args)
+        .current_dir(repo_dir)
+        .output()?;
+    Ok(())
+}
+
+/// Create a repo at `superproject_dir` with the fixture repo as a submodule named
+/// `submodule`, and return the path of the submodule
+pub fn create_fixture_submodule(superproject_dir: &Path) -> io::Result<PathBuf> {
+    let fixture_repo_path = create_fixture_repo()?;
+    fs::create_dir_all(superproject_dir)?;
+
+    Command::new("git")
+        .args(&["init"])
+        .current_dir(superproject_dir)
+        .output()?;
+
+    // Cloning submodules from local paths is disabled by default since git 2.38.1
+    Command::new("git")
+        .args(&["-c", "protocol.file.allow=always", "submodule", "add"])
+        .args(&[&fixture_repo_path, Path::new("submodule")])
+        .current_dir(superproject_dir)
+        .output()?;
+
+    remove_dir_all(fixture_repo_path)?;
+    Ok(superproject_dir.join("submodule"))
+}
+
 fn path_str(repo_dir: &PathBuf) -> io::Result<String> {
     repo_dir
         .to_str()
@@ -39,3 +39,271 @@ fn add_newline_configuration() -> io::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn async_render_configuration() -> io::Result<()> {
+    let cache_dir = tempfile::tempdir()?;
+    let config = toml::toml! {
+        add_newline = false
+        prompt_order = ["line_break", "character"]
+        async_render = true
+        async_modules = ["line_break"]
+    };
+
+    // Slow modules are replaced by a placeholder, and the shell is told to render again
+    let output = common::render_prompt()
+        .use_config(config.clone())
+        .env("STARSHIP_CACHE", cache_dir.path())
+        .arg("--async=fast")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("… {} ", Color::Green.bold().paint("❯"));
+    assert_eq!(expected, actual);
+    assert_eq!(Some(75), output.status.code());
+
+    // The second phase renders every module
+    let output = common::render_prompt()
+        .use_config(config)
+        .env("STARSHIP_CACHE", cache_dir.path())
+        .arg("--async=full")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("\n{} ", Color::Green.bold().paint("❯"));
+    assert_eq!(expected, actual);
+    assert_eq!(Some(0), output.status.code());
+
+    cache_dir.close()
+}
+
+#[test]
+fn async_render_skips_git_modules_outside_repository() -> io::Result<()> {
+    let cache_dir = tempfile::tempdir()?;
+    let dir = tempfile::tempdir()?;
+
+    let output = common::render_prompt()
+        .use_config(toml::toml! {
+            add_newline = false
+            prompt_order = ["git_status", "character"]
+            async_render = true
+            async_modules = ["git_status"]
+        })
+        .env("STARSHIP_CACHE", cache_dir.path())
+        .arg("--async=fast")
+        .arg("--path")
+        .arg(dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Green.bold().paint("❯"));
+    assert_eq!(expected, actual);
+    assert_eq!(Some(0), output.status.code());
+
+    dir.close()?;
+    cache_dir.close()
+}
+
+#[test]
+fn async_render_remembers_slow_modules_per_directory() -> io::Result<()> {
+    let cache_dir = tempfile::tempdir()?;
+    let slow_dir = tempfile::tempdir()?;
+    let other_dir = tempfile::tempdir()?;
+    let config = toml::toml! {
+        add_newline = false
+        prompt_order = ["custom.slow"]
+        async_render = true
+        async_modules = []
+        async_threshold = 1
+        [custom.slow]
+        command = "sleep 0.05; echo slow"
+        when = "true"
+    };
+
+    common::render_prompt()
+        .use_config(config.clone())
+        .env("STARSHIP_CACHE", cache_dir.path())
+        .arg("--async=full")
+        .arg("--path")
+        .arg(slow_dir.path())
+        .output()?;
+
+    let output = common::render_prompt()
+        .use_config(config.clone())
+        .env("STARSHIP_CACHE", cache_dir.path())
+        .arg("--async=fast")
+        .arg("--path")
+        .arg(slow_dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(actual.contains('…'));
+    assert_eq!(Some(75), output.status.code());
+
+    // It was only measured as slow in the other directory
+    let output = common::render_prompt()
+        .use_config(config)
+        .env("STARSHIP_CACHE", cache_dir.path())
+        .arg("--async=fast")
+        .arg("--path")
+        .arg(other_dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(actual.contains("slow"));
+    assert_eq!(Some(0), output.status.code());
+
+    cache_dir.close()?;
+    slow_dir.close()?;
+    other_dir.close()
+}
+
+#[test]
+fn async_render_disabled_by_default() -> io::Result<()> {
+    let output = common::render_prompt()
+        .use_config(toml::toml! {
+            add_newline = false
+            prompt_order = ["line_break", "character"]
+            async_modules = ["line_break"]
+        })
+        .arg("--async=fast")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("\n{} ", Color::Green.bold().paint("❯"));
+    assert_eq!(expected, actual);
+    assert_eq!(Some(0), output.status.code());
+
+    Ok(())
+}
+
+#[test]
+fn shell_integration_marks_prompt() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("a b");
+    std::fs::create_dir(&path)?;
+
+    let output = common::render_prompt()
+        .use_config(toml::toml! {
+            add_newline = false
+            prompt_order = ["character"]
+            shell_integration = true
+        })
+        .arg("--status=1")
+        .arg("--path")
+        .arg(&path)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(actual.starts_with("\x1b]133;D;1\x07\x1b]133;A\x07\x1b]7;file://"));
+    assert!(actual.contains("/a%20b\x07"));
+    assert!(actual.ends_with("\x1b]133;B\x07"));
+
+    // Marks aren't printed unless they are enabled
+    let output = common::render_prompt()
+        .use_config(toml::toml! {
+            add_newline = false
+            prompt_order = ["character"]
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(!actual.contains("\x1b]"));
+
+    dir.close()
+}
+
+#[test]
+fn module_text_escaped_for_shell() -> io::Result<()> {
+    let render = |shell: &str, args: &[&str]| -> io::Result<String> {
+        let output = common::render_prompt()
+            .use_config(toml::toml! {
+                add_newline = false
+                prompt_order = ["env_var"]
+                [env_var]
+                variable = "TEST_VAR"
+                style = ""
+                prefix = ""
+                suffix = ""
+            })
+            .env("STARSHIP_SHELL", shell)
+            .env("TEST_VAR", r"$(echo hi) `echo hi` a\b 100%")
+            .args(args)
+            .output()?;
+        Ok(String::from_utf8(output.stdout).unwrap())
+    };
+
+    assert_eq!(
+        render("bash", &[])?,
+        r"\\$(echo hi) \\`echo hi\\` a\\\\b 100% "
+    );
+    assert_eq!(
+        render("bash", &["--no-promptvars"])?,
+        r"$(echo hi) `echo hi` a\\b 100% "
+    );
+    assert_eq!(render("zsh", &[])?, r"\$(echo hi) \`echo hi\` a\\b 100%% ");
+    assert_eq!(
+        render("zsh", &["--no-promptvars"])?,
+        r"$(echo hi) `echo hi` a\b 100%% "
+    );
+    assert_eq!(render("ion", &[])?, r"$(echo hi) `echo hi` a\b 100% ");
+
+    // `starship module` escapes the text the same way
+    let output = common::render_module("env_var")
+        .use_config(toml::toml! {
+            [env_var]
+            variable = "TEST_VAR"
+            style = ""
+            prefix = ""
+            suffix = ""
+        })
+        .env("STARSHIP_SHELL", "zsh")
+        .env("TEST_VAR", "100%")
+        .output()?;
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "with 100%% ");
+    Ok(())
+}
+
+#[test]
+fn scan_parents_finds_project_files() -> io::Result<()> {
+    let project_dir = tempfile::tempdir()?;
+    std::fs::File::create(project_dir.path().join("Cargo.toml"))?.sync_all()?;
+    std::fs::create_dir(project_dir.path().join("src"))?;
+    git2::Repository::init(project_dir.path()).unwrap();
+
+    let render_rust = |scan_parents: bool| -> io::Result<String> {
+        let output = common::render_module("rust")
+            .use_config(toml::toml! {
+                scan_parents = scan_parents
+            })
+            .arg("--path")
+            .arg(project_dir.path().join("src"))
+            .output()?;
+        Ok(String::from_utf8(output.stdout).unwrap())
+    };
+
+    assert_eq!("", render_rust(false)?);
+    assert!(render_rust(true)?.contains("🦀"));
+    project_dir.close()
+}
+
+#[test]
+fn scan_parents_stops_at_home_outside_repository() -> io::Result<()> {
+    let home_dir = tempfile::tempdir()?;
+    std::fs::create_dir_all(home_dir.path().join("project/src"))?;
+    std::fs::File::create(home_dir.path().join("project/Cargo.toml"))?.sync_all()?;
+
+    let render_rust = |dir: &str| -> io::Result<String> {
+        let output = common::render_module("rust")
+            .use_config(toml::toml! {
+                scan_parents = true
+            })
+            .env("HOME", home_dir.path())
+            .arg("--path")
+            .arg(home_dir.path().join(dir))
+            .output()?;
+        Ok(String::from_utf8(output.stdout).unwrap())
+    };
+
+    assert!(render_rust("project/src")?.contains("🦀"));
+
+    // Files in the home directory itself don't make a project
+    std::fs::rename(
+        home_dir.path().join("project/Cargo.toml"),
+        home_dir.path().join("Cargo.toml"),
+    )?;
+    assert_eq!("", render_rust("project/src")?);
+    home_dir.close()
+}
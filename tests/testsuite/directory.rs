@@ -481,6 +481,71 @@ fn directory_in_git_repo_truncate_to_repo_true() -> io::Result<()> {
     tmp_dir.close()
 }
 
+#[test]
+#[ignore]
+fn directory_in_submodule() -> io::Result<()> {
+    let tmp_dir = TempDir::new_in(dirs::home_dir().unwrap())?;
+    let submodule = common::create_fixture_submodule(&tmp_dir.path().join("rocket"))?;
+
+    let output = common::render_module("directory")
+        .use_config(toml::toml! {
+            [directory]
+            submodule_symbol = "⊂ "
+        })
+        .arg("--path")
+        .arg(&submodule)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("in {} ", Color::Cyan.bold().paint("⊂ submodule"));
+    assert_eq!(expected, actual);
+    tmp_dir.close()
+}
+
+#[test]
+#[ignore]
+fn directory_in_submodule_truncate_to_repo_false() -> io::Result<()> {
+    let tmp_dir = TempDir::new_in(dirs::home_dir().unwrap())?;
+    let submodule = common::create_fixture_submodule(&tmp_dir.path().join("rocket"))?;
+
+    let output = common::render_module("directory")
+        .use_config(toml::toml! {
+            [directory]
+            submodule_symbol = "⊂ "
+            truncate_to_repo = false
+        })
+        .arg("--path")
+        .arg(&submodule)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    // The path isn't contracted to the submodule, so it isn't marked as one
+    assert!(actual.contains("rocket/submodule"));
+    assert!(!actual.contains('⊂'));
+    tmp_dir.close()
+}
+
+#[test]
+#[ignore]
+fn directory_in_submodule_truncate_to_superproject() -> io::Result<()> {
+    let tmp_dir = TempDir::new_in(dirs::home_dir().unwrap())?;
+    let submodule = common::create_fixture_submodule(&tmp_dir.path().join("rocket"))?;
+
+    let output = common::render_module("directory")
+        .use_config(toml::toml! {
+            [directory]
+            truncate_to_superproject = true
+        })
+        .arg("--path")
+        .arg(&submodule)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("in {} ", Color::Cyan.bold().paint("rocket/submodule"));
+    assert_eq!(expected, actual);
+    tmp_dir.close()
+}
+
 #[test]
 #[ignore]
 #[cfg(not(target_os = "windows"))]
@@ -511,3 +576,26 @@ fn git_repo_in_home_directory_truncate_to_repo_true() -> io::Result<()> {
     assert_eq!(expected, actual);
     tmp_dir.close()
 }
+
+#[test]
+fn hyperlinked_directory() -> io::Result<()> {
+    let output = common::render_module("directory")
+        .arg("--path=/tmp/some dir")
+        .use_config(toml::toml! {
+            [directory]
+            hyperlink = true
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let host = gethostname::gethostname();
+    let expected = format!(
+        "in {} ",
+        Color::Cyan.bold().paint(format!(
+            "\x1b]8;;file://{}/tmp/some%20dir\x07/tmp/some dir\x1b]8;;\x07",
+            host.to_string_lossy()
+        ))
+    );
+    assert_eq!(expected, actual);
+    Ok(())
+}
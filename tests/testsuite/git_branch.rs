@@ -1,4 +1,4 @@
-use ansi_term::Color;
+use ansi_term::{ANSIStrings, Color};
 use remove_dir_all::remove_dir_all;
 use std::io;
 use std::process::Command;
@@ -122,6 +122,305 @@ fn test_works_with_unborn_master() -> io::Result<()> {
     remove_dir_all(repo_dir)
 }
 
+#[test]
+fn test_works_with_unborn_branch_from_head() -> io::Result<()> {
+    let repo_dir = tempfile::tempdir()?.into_path();
+
+    Command::new("git")
+        .args(&["init"])
+        .current_dir(&repo_dir)
+        .output()?;
+
+    Command::new("git")
+        .args(&["symbolic-ref", "HEAD", "refs/heads/main"])
+        .current_dir(&repo_dir)
+        .output()?;
+
+    let output = common::render_module("git_branch")
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("on {} ", Color::Purple.bold().paint("\u{e0a0} main"));
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn test_detached_head_on_tag() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    Command::new("git")
+        .args(&["tag", "v1.0", "@~1"])
+        .current_dir(&repo_dir)
+        .output()?;
+
+    Command::new("git")
+        .args(&["checkout", "v1.0"])
+        .current_dir(&repo_dir)
+        .output()?;
+
+    let output = common::render_module("git_branch")
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("on {} ", Color::Purple.bold().paint("\u{e0a0} v1.0"));
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn test_detached_head_described() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    Command::new("git")
+        .args(&["tag", "v1.0", "@~1"])
+        .current_dir(&repo_dir)
+        .output()?;
+
+    Command::new("git")
+        .args(&["checkout", "--detach"])
+        .current_dir(&repo_dir)
+        .output()?;
+
+    let git_output = Command::new("git")
+        .args(&["describe", "--tags", "--abbrev=7"])
+        .current_dir(&repo_dir)
+        .output()?
+        .stdout;
+    let description = String::from_utf8(git_output).unwrap();
+
+    let output = common::render_module("git_branch")
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "on {} ",
+        Color::Purple
+            .bold()
+            .paint(format!("\u{e0a0} {}", description.trim())),
+    );
+    assert!(description.starts_with("v1.0-1-g"));
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn test_detached_head_hash() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    Command::new("git")
+        .args(&["tag", "v1.0", "@~1"])
+        .current_dir(&repo_dir)
+        .output()?;
+
+    Command::new("git")
+        .args(&["checkout", "--detach"])
+        .current_dir(&repo_dir)
+        .output()?;
+
+    let mut git_output = Command::new("git")
+        .args(&["rev-parse", "HEAD"])
+        .current_dir(&repo_dir)
+        .output()?
+        .stdout;
+    git_output.truncate(10);
+    let expected_hash = String::from_utf8(git_output).unwrap();
+
+    let output = common::render_module("git_branch")
+        .use_config(toml::toml! {
+            [git_branch]
+            detached_describe = false
+            detached_hash_length = 10
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "on {} ",
+        Color::Purple
+            .bold()
+            .paint(format!("\u{e0a0} {}", expected_hash)),
+    );
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn test_linked_worktree() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+    let worktree_dir = tempfile::tempdir()?;
+    let worktree = worktree_dir.path().join("rocket-wt");
+
+    Command::new("git")
+        .args(&["worktree", "add", "-b", "feature"])
+        .arg(&worktree)
+        .current_dir(&repo_dir)
+        .output()?;
+
+    let output = common::render_module("git_branch")
+        .arg("--path")
+        .arg(&worktree)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("on {} ", Color::Purple.bold().paint("\u{e0a0} feature"));
+    assert_eq!(expected, actual);
+
+    let output = common::render_module("git_branch")
+        .use_config(toml::toml! {
+            [git_branch]
+            worktree_symbol = " 🌳 "
+        })
+        .arg("--path")
+        .arg(&worktree)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "on {} ",
+        Color::Purple.bold().paint("\u{e0a0} feature 🌳 rocket-wt"),
+    );
+    assert_eq!(expected, actual);
+    worktree_dir.close()?;
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn test_submodule() -> io::Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let submodule = common::create_fixture_submodule(&tmp_dir.path().join("superproject"))?;
+
+    let output = common::render_module("git_branch")
+        .arg("--path")
+        .arg(&submodule)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "on {} ",
+        Color::Purple
+            .bold()
+            .paint("\u{e0a0} master in superproject"),
+    );
+    assert_eq!(expected, actual);
+    tmp_dir.close()
+}
+
+#[test]
+fn test_upstream_with_another_name() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    Command::new("git")
+        .args(&["checkout", "-b", "feature", "--track", "origin/master"])
+        .current_dir(&repo_dir)
+        .output()?;
+
+    let output = common::render_module("git_branch")
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "on {} ",
+        Color::Purple.bold().paint("\u{e0a0} feature:origin/master"),
+    );
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn test_always_show_remote() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    let output = common::render_module("git_branch")
+        .use_config(toml::toml! {
+            [git_branch]
+            always_show_remote = true
+            remote_name = { value = "", style = "green" }
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "on {} ",
+        ANSIStrings(&[
+            Color::Purple.bold().paint("\u{e0a0} master:"),
+            Color::Green.paint("origin/"),
+            Color::Purple.bold().paint("master"),
+        ])
+    );
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn test_no_upstream_symbol() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    Command::new("git")
+        .args(&["checkout", "-b", "local"])
+        .current_dir(&repo_dir)
+        .output()?;
+
+    let output = common::render_module("git_branch")
+        .use_config(toml::toml! {
+            [git_branch]
+            no_upstream_symbol = " ⇡?"
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("on {} ", Color::Purple.bold().paint("\u{e0a0} local ⇡?"),);
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn test_branch_name_escaped_for_zsh() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    // Branch names can't contain spaces, so this stands in for `$(echo x)`
+    Command::new("git")
+        .args(&["checkout", "-b", "$(echo)x`echo`"])
+        .current_dir(&repo_dir)
+        .output()?;
+
+    let render = |args: &[&str]| -> io::Result<String> {
+        let output = common::render_prompt()
+            .use_config(toml::toml! {
+                add_newline = false
+                prompt_order = ["git_branch"]
+                [git_branch]
+                prefix = ""
+                style = ""
+            })
+            .env("STARSHIP_SHELL", "zsh")
+            .arg("--path")
+            .arg(&repo_dir)
+            .args(args)
+            .output()?;
+        Ok(String::from_utf8(output.stdout).unwrap())
+    };
+
+    // With prompt_subst, zsh would run the commands in the branch name
+    assert_eq!(render(&[])?, "\u{e0a0} \\$(echo)x\\`echo\\` ");
+    assert_eq!(render(&["--no-promptvars"])?, "\u{e0a0} $(echo)x`echo` ");
+    remove_dir_all(repo_dir)
+}
+
 fn test_truncate_length(
     branch_name: &str,
     truncate_length: i64,
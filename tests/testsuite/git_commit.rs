@@ -130,3 +130,205 @@ fn test_render_commit_hash_only_detached_on_detached() -> io::Result<()> {
     assert_eq!(expected, actual);
     remove_dir_all(repo_dir)
 }
+
+#[test]
+fn test_render_tag_on_detached() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    Command::new("git")
+        .args(&["tag", "v1.0", "@~1"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+
+    Command::new("git")
+        .args(&["checkout", "v1.0"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+
+    let output = common::render_module("git_commit")
+        .use_config(toml::toml! {
+            [git_commit]
+                detached_tag = true
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = Color::Green.bold().paint("(v1.0) ").to_string();
+
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn test_render_tags() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    Command::new("git")
+        .args(&["tag", "v1.0"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+
+    Command::new("git")
+        .args(&["tag", "-a", "v1.0-annotated", "-m", "Release v1.0"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+
+    Command::new("git")
+        .args(&["tag", "v0.9", "@~1"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+
+    let mut git_output = Command::new("git")
+        .args(&["rev-parse", "HEAD"])
+        .current_dir(repo_dir.as_path())
+        .output()?
+        .stdout;
+    git_output.truncate(7);
+    let expected_hash = str::from_utf8(&git_output).unwrap();
+
+    let output = common::render_module("git_commit")
+        .use_config(toml::toml! {
+            [git_commit]
+                only_detached = false
+                tag_disabled = false
+                tag_symbol = " "
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = Color::Green
+        .bold()
+        .paint(format!("({} v1.0, v1.0-annotated) ", expected_hash))
+        .to_string();
+
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn test_render_tags_without_detached_name() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    for tag in &["v1.0", "v1.0-rc"] {
+        Command::new("git")
+            .args(&["tag", tag])
+            .current_dir(repo_dir.as_path())
+            .output()?;
+    }
+
+    Command::new("git")
+        .args(&["checkout", "--detach"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+
+    let output = common::render_module("git_commit")
+        .use_config(toml::toml! {
+            [git_commit]
+                detached_tag = true
+                tag_disabled = false
+                tag_symbol = " "
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = Color::Green.bold().paint("(v1.0 v1.0-rc) ").to_string();
+
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn test_render_tags_without_git_branch_name() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    for tag in &["v1.0", "v1.0-rc"] {
+        Command::new("git")
+            .args(&["tag", tag])
+            .current_dir(repo_dir.as_path())
+            .output()?;
+    }
+
+    Command::new("git")
+        .args(&["checkout", "--detach"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+
+    let mut git_output = Command::new("git")
+        .args(&["rev-parse", "HEAD"])
+        .current_dir(repo_dir.as_path())
+        .output()?
+        .stdout;
+    git_output.truncate(7);
+    let expected_hash = str::from_utf8(&git_output).unwrap();
+
+    let output = common::render_module("git_commit")
+        .use_config(toml::toml! {
+            [git_commit]
+                tag_disabled = false
+                tag_hide_detached_name = true
+                tag_symbol = " "
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = Color::Green
+        .bold()
+        .paint(format!("({} v1.0-rc) ", expected_hash))
+        .to_string();
+
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn test_render_commit_hash_hyperlink() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    Command::new("git")
+        .args(&[
+            "remote",
+            "set-url",
+            "origin",
+            "git@github.com:starship/starship.git",
+        ])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+    let git_output = Command::new("git")
+        .args(&["rev-parse", "HEAD"])
+        .current_dir(repo_dir.as_path())
+        .output()?
+        .stdout;
+    let full_hash = str::from_utf8(&git_output).unwrap().trim();
+
+    let output = common::render_module("git_commit")
+        .use_config(toml::toml! {
+            [git_commit]
+                only_detached = false
+                hyperlink = true
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!(
+        "\x1b]8;;https://github.com/starship/starship/commit/{}\x07{}\x1b]8;;\x07",
+        full_hash,
+        &full_hash[..7]
+    );
+
+    assert!(actual.contains(&expected));
+    remove_dir_all(repo_dir)
+}
@@ -0,0 +1,108 @@
+use ansi_term::Color;
+use remove_dir_all::remove_dir_all;
+use std::fs;
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn show_nothing_on_empty_dir() -> io::Result<()> {
+    let repo_dir = tempfile::tempdir()?;
+
+    let output = common::render_module("git_last_commit")
+        .use_config(toml::toml! {
+            [git_last_commit]
+            disabled = false
+        })
+        .arg("--path")
+        .arg(repo_dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = "";
+    assert_eq!(expected, actual);
+    repo_dir.close()
+}
+
+#[test]
+fn shows_commit_age() -> io::Result<()> {
+    let repo_dir = common::create_repo_with_commit(3 * 3_600 + 60)?;
+
+    let output = common::render_module("git_last_commit")
+        .use_config(toml::toml! {
+            [git_last_commit]
+            disabled = false
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("committed {} ", Color::Blue.bold().paint("3h ago"));
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn shows_commit_author() -> io::Result<()> {
+    let repo_dir = common::create_repo_with_commit(2 * 86_400)?;
+
+    let output = common::render_module("git_last_commit")
+        .use_config(toml::toml! {
+            [git_last_commit]
+            disabled = false
+            show_author = true
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "committed {} ",
+        Color::Blue.bold().paint("2d ago by starship")
+    );
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn changes_style_when_uncommitted_for_long() -> io::Result<()> {
+    let repo_dir = common::create_repo_with_commit(2 * 3_600)?;
+    fs::write(repo_dir.join("Cargo.toml"), "[package]")?;
+
+    let output = common::render_module("git_last_commit")
+        .use_config(toml::toml! {
+            [git_last_commit]
+            disabled = false
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("committed {} ", Color::Yellow.bold().paint("2h ago"));
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn looks_inside_untracked_directories() -> io::Result<()> {
+    let repo_dir = common::create_repo_with_commit(2 * 3_600)?;
+    fs::create_dir_all(repo_dir.join("new/nested"))?;
+    fs::write(repo_dir.join("new/nested/lib.rs"), "")?;
+
+    let output = common::render_module("git_last_commit")
+        .use_config(toml::toml! {
+            [git_last_commit]
+            disabled = false
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("committed {} ", Color::Yellow.bold().paint("2h ago"));
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
@@ -0,0 +1,167 @@
+use ansi_term::Color;
+use remove_dir_all::remove_dir_all;
+use std::fs;
+use std::io;
+use std::process::Command;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn show_nothing_on_empty_dir() -> io::Result<()> {
+    let repo_dir = tempfile::tempdir()?;
+
+    let output = common::render_module("git_metrics")
+        .use_config(toml::toml! {
+            [git_metrics]
+            disabled = false
+        })
+        .arg("--path")
+        .arg(repo_dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = "";
+    assert_eq!(expected, actual);
+    repo_dir.close()
+}
+
+#[test]
+fn show_nothing_on_clean_repo() -> io::Result<()> {
+    let repo_dir = common::create_repo_with_commit(0)?;
+
+    let output = common::render_module("git_metrics")
+        .use_config(toml::toml! {
+            [git_metrics]
+            disabled = false
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = "";
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn shows_added_and_deleted_lines() -> io::Result<()> {
+    let repo_dir = common::create_repo_with_commit(0)?;
+    fs::write(repo_dir.join("lines.txt"), "a\nx\ny\nz\n")?;
+
+    let output = common::render_module("git_metrics")
+        .use_config(toml::toml! {
+            [git_metrics]
+            disabled = false
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "{} {} ",
+        Color::Green.bold().paint("+3"),
+        Color::Red.bold().paint("-2")
+    );
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn shows_only_added_lines() -> io::Result<()> {
+    let repo_dir = common::create_repo_with_commit(0)?;
+    fs::write(repo_dir.join("lines.txt"), "a\nb\nc\nd\ne\n")?;
+
+    let output = common::render_module("git_metrics")
+        .use_config(toml::toml! {
+            [git_metrics]
+            disabled = false
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("{} ", Color::Green.bold().paint("+2"));
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn shows_staged_lines() -> io::Result<()> {
+    let repo_dir = common::create_repo_with_commit(0)?;
+    fs::write(repo_dir.join("lines.txt"), "a\nb\n")?;
+
+    Command::new("git")
+        .args(&["add", "lines.txt"])
+        .current_dir(&repo_dir)
+        .output()?;
+
+    let output = common::render_module("git_metrics")
+        .use_config(toml::toml! {
+            [git_metrics]
+            disabled = false
+            only_nonzero_diffs = false
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "{} {} ",
+        Color::Green.bold().paint("+0"),
+        Color::Red.bold().paint("-1")
+    );
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn excludes_staged_lines() -> io::Result<()> {
+    let repo_dir = common::create_repo_with_commit(0)?;
+    fs::write(repo_dir.join("lines.txt"), "a\nb\n")?;
+
+    Command::new("git")
+        .args(&["add", "lines.txt"])
+        .current_dir(&repo_dir)
+        .output()?;
+
+    let output = common::render_module("git_metrics")
+        .use_config(toml::toml! {
+            [git_metrics]
+            disabled = false
+            include_staged = false
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = "";
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn skips_large_repos() -> io::Result<()> {
+    let repo_dir = common::create_repo_with_commit(0)?;
+    fs::write(repo_dir.join("lines.txt"), "a\nx\ny\nz\n")?;
+
+    let output = common::render_module("git_metrics")
+        .use_config(toml::toml! {
+            [git_metrics]
+            disabled = false
+            [git_status]
+            large_repo_threshold = 1
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = "";
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
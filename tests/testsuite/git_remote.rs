@@ -0,0 +1,174 @@
+use ansi_term::Color;
+use remove_dir_all::remove_dir_all;
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn show_nothing_on_empty_dir() -> io::Result<()> {
+    let repo_dir = tempfile::tempdir()?;
+
+    let output = common::render_module("git_remote")
+        .use_config(toml::toml! {
+            [git_remote]
+            disabled = false
+        })
+        .arg("--path")
+        .arg(repo_dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = "";
+    assert_eq!(expected, actual);
+    repo_dir.close()
+}
+
+#[test]
+fn shows_github_remote() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+    common::git(
+        &repo_dir,
+        &[
+            "remote",
+            "set-url",
+            "origin",
+            "git@github.com:starship/starship.git",
+        ],
+    )?;
+
+    let output = common::render_module("git_remote")
+        .use_config(toml::toml! {
+            [git_remote]
+            disabled = false
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "at {} ",
+        Color::Blue.bold().paint("\u{f09b} starship/starship")
+    );
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn applies_instead_of_rewrites() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+    common::git(
+        &repo_dir,
+        &["remote", "set-url", "origin", "gl:group/project"],
+    )?;
+    common::git(
+        &repo_dir,
+        &["config", "url.git@gitlab.com:.insteadOf", "gl:"],
+    )?;
+
+    let output = common::render_module("git_remote")
+        .use_config(toml::toml! {
+            [git_remote]
+            disabled = false
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("at {} ", Color::Blue.bold().paint("\u{f296} group/project"));
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn applies_push_instead_of_rewrites() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+    common::git(
+        &repo_dir,
+        &[
+            "remote",
+            "set-url",
+            "origin",
+            "https://github.com/starship/starship.git",
+        ],
+    )?;
+    common::git(
+        &repo_dir,
+        &[
+            "config",
+            "url.git@gitlab.com:mirror/.pushInsteadOf",
+            "https://github.com/",
+        ],
+    )?;
+
+    let output = common::render_module("git_remote")
+        .use_config(toml::toml! {
+            [git_remote]
+            disabled = false
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "at {} ",
+        Color::Blue
+            .bold()
+            .paint("\u{f296} mirror/starship/starship")
+    );
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn shows_push_url_of_tracked_remote() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+    common::git(
+        &repo_dir,
+        &[
+            "remote",
+            "set-url",
+            "origin",
+            "git@github.com:starship/starship.git",
+        ],
+    )?;
+    common::git(
+        &repo_dir,
+        &[
+            "remote",
+            "add",
+            "fork",
+            "https://git.corp.com/team/starship.git",
+        ],
+    )?;
+    common::git(
+        &repo_dir,
+        &[
+            "config",
+            "remote.fork.pushurl",
+            "git@git.corp.com:me/starship.git",
+        ],
+    )?;
+    common::git(&repo_dir, &["config", "branch.master.remote", "fork"])?;
+
+    let output = common::render_module("git_remote")
+        .use_config(toml::toml! {
+            [git_remote]
+            disabled = false
+            show_host = true
+            symbols = { "corp.com" = "🏢 " }
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "at {} ",
+        Color::Blue.bold().paint("🏢 git.corp.com/me/starship")
+    );
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
@@ -1,4 +1,4 @@
-use super::common;
+use super::common::{self, TestCommand};
 use std::ffi::OsStr;
 use std::fs::OpenOptions;
 use std::io::{self, Error, ErrorKind, Write};
@@ -53,6 +53,76 @@ fn shows_merging() -> io::Result<()> {
     Ok(())
 }
 
+#[test]
+#[ignore]
+fn shows_rebase_details() -> io::Result<()> {
+    let repo_dir = create_repo_with_conflict()?;
+    let path = path_str(&repo_dir)?;
+
+    run_git_cmd(&["rebase", "other-branch"], Some(path), false)?;
+
+    let output = common::render_module("git_state")
+        .use_config(toml::toml! {
+            [git_state]
+            show_step = true
+        })
+        .current_dir(path)
+        .output()?;
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("REBASING 1/1 master onto other-branch: Commit C =1"));
+
+    Ok(())
+}
+
+#[test]
+#[ignore]
+fn shows_rebasing_in_linked_worktree() -> io::Result<()> {
+    let repo_dir = create_repo_with_conflict()?;
+    let path = path_str(&repo_dir)?;
+    let worktree_dir = tempfile::tempdir()?;
+    let worktree = worktree_dir.path().join("worktree");
+    let worktree_path = worktree.to_str().unwrap();
+
+    run_git_cmd(
+        &[
+            "worktree",
+            "add",
+            "-b",
+            "wt-branch",
+            worktree_path,
+            "master",
+        ],
+        Some(path),
+        true,
+    )?;
+    run_git_cmd(&["rebase", "other-branch"], Some(worktree_path), false)?;
+
+    let output = common::render_module("git_state")
+        .current_dir(worktree_path)
+        .output()?;
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("REBASING 1/1 wt-branch onto other-branch"));
+
+    Ok(())
+}
+
+#[test]
+#[ignore]
+fn shows_merge_details() -> io::Result<()> {
+    let repo_dir = create_repo_with_conflict()?;
+    let path = path_str(&repo_dir)?;
+
+    run_git_cmd(&["merge", "other-branch"], Some(path), false)?;
+
+    let output = common::render_module("git_state")
+        .current_dir(path)
+        .output()?;
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("MERGING other-branch =1"));
+
+    Ok(())
+}
+
 #[test]
 #[ignore]
 fn shows_cherry_picking() -> io::Result<()> {
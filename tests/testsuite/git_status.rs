@@ -165,6 +165,61 @@ fn shows_diverged_with_count() -> io::Result<()> {
     remove_dir_all(repo_dir)
 }
 
+#[test]
+#[ignore]
+fn shows_ahead_behind_base() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    Command::new("git")
+        .args(&["checkout", "-b", "feature"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+    barrier();
+
+    diverge(&repo_dir)?;
+
+    let output = common::render_module("git_status")
+        .use_config(toml::toml! {
+            [git_status]
+            compare_to = ["origin/main", "origin/master"]
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = Color::Red
+        .bold()
+        .paint(format!("[{}] ", "↑1↓1"))
+        .to_string();
+
+    assert_eq!(expected, actual);
+
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+#[ignore]
+fn doesnt_compare_upstream_with_itself() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    diverge(&repo_dir)?;
+
+    let output = common::render_module("git_status")
+        .use_config(toml::toml! {
+            [git_status]
+            compare_to = ["origin/master"]
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = Color::Red.bold().paint(format!("[{}] ", "⇕")).to_string();
+
+    assert_eq!(expected, actual);
+
+    remove_dir_all(repo_dir)
+}
+
 #[test]
 #[ignore]
 fn shows_conflicted() -> io::Result<()> {
@@ -546,6 +601,147 @@ fn suffix() -> io::Result<()> {
     remove_dir_all(repo_dir)
 }
 
+#[test]
+#[ignore]
+fn shows_dirty_in_large_repo() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    create_staged(&repo_dir)?;
+    create_modified(&repo_dir)?;
+
+    let output = common::render_module("git_status")
+        .use_config(toml::toml! {
+            [git_status]
+            large_repo_threshold = 1
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = Color::Red.bold().paint(format!("[{}] ", "*")).to_string();
+
+    assert_eq!(expected, actual);
+
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+#[ignore]
+fn doesnt_scan_worktree_in_large_repo() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    create_untracked(&repo_dir)?;
+    create_modified(&repo_dir)?;
+
+    let output = common::render_module("git_status")
+        .use_config(toml::toml! {
+            [git_status]
+            large_repo_threshold = 1
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = "";
+
+    assert_eq!(expected, actual);
+
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+#[ignore]
+fn shows_status_with_untracked_cache() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    Command::new("git")
+        .args(&["config", "core.untrackedCache", "true"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+    barrier();
+
+    create_staged(&repo_dir)?;
+    create_modified(&repo_dir)?;
+    File::create(repo_dir.join("notes"))?.sync_all()?;
+
+    let output = common::render_module("git_status")
+        .use_config(toml::toml! {
+            [git_status]
+            modified_count.enabled = true
+            staged_count.enabled = true
+            untracked_count.enabled = true
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = Color::Red
+        .bold()
+        .paint(format!("[{}] ", "!1+1?1"))
+        .to_string();
+
+    assert_eq!(expected, actual);
+
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+#[ignore]
+fn renders_alongside_other_git_modules() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    create_modified(&repo_dir)?;
+    create_staged(&repo_dir)?;
+    Command::new("git")
+        .args(&["tag", "v1.0"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+    Command::new("git")
+        .args(&["checkout", "--detach"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+    barrier();
+
+    let git_modules = [
+        "git_branch",
+        "git_commit",
+        "git_state",
+        "git_status",
+        "git_metrics",
+        "git_last_commit",
+    ];
+    let config = toml::toml! {
+        prompt_order = ["character", "git_branch", "git_commit", "git_state", "git_status", "git_metrics", "git_last_commit"]
+        [git_commit]
+        tag_disabled = false
+        tag_hide_detached_name = true
+        [git_metrics]
+        disabled = false
+    };
+
+    // Every module is computed in parallel, sharing one repository
+    let output = common::render_prompt()
+        .use_config(config.clone())
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    for name in &git_modules {
+        let output = common::render_module(name)
+            .use_config(config.clone())
+            .arg("--path")
+            .arg(&repo_dir)
+            .output()?;
+        let expected = String::from_utf8(output.stdout).unwrap();
+        assert!(actual.contains(&expected), "{} is missing", name);
+    }
+    // git_branch names the detached HEAD by its tag, so git_commit leaves it out
+    assert_eq!(actual.matches("v1.0").count(), 1);
+    assert!(actual.contains("[+]"));
+    remove_dir_all(repo_dir)
+}
+
 fn ahead(repo_dir: &PathBuf) -> io::Result<()> {
     File::create(repo_dir.join("readme.md"))?.sync_all()?;
 
@@ -0,0 +1,123 @@
+use ansi_term::Color;
+use remove_dir_all::remove_dir_all;
+use std::io;
+use std::process::Command;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn show_nothing_on_empty_dir() -> io::Result<()> {
+    let repo_dir = tempfile::tempdir()?;
+
+    let output = common::render_module("git_user")
+        .use_config(toml::toml! {
+            [git_user]
+            disabled = false
+        })
+        .arg("--path")
+        .arg(repo_dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = "";
+    assert_eq!(expected, actual);
+    repo_dir.close()
+}
+
+#[test]
+fn shows_email() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    let output = common::render_module("git_user")
+        .use_config(toml::toml! {
+            [git_user]
+            disabled = false
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("as {} ", Color::Cyan.bold().paint("starship@example.com"));
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn shows_name_and_email() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    let output = common::render_module("git_user")
+        .use_config(toml::toml! {
+            [git_user]
+            disabled = false
+            show_name = true
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "as {} ",
+        Color::Cyan.bold().paint("starship starship@example.com")
+    );
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn shows_alias_of_most_specific_pattern() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    let output = common::render_module("git_user")
+        .use_config(toml::toml! {
+            [git_user]
+            disabled = false
+            [git_user.aliases]
+            "*" = "anyone"
+            "*@example.com" = { value = "example", style = "green" }
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("as {} ", Color::Green.paint("example"));
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn shows_warning_without_identity() -> io::Result<()> {
+    let repo_dir = tempfile::tempdir()?;
+    // Without $HOME, git would fall back to the home directory of the user, and
+    // find their identity there
+    let home_dir = tempfile::tempdir()?;
+
+    Command::new("git")
+        .args(&["init"])
+        .env("HOME", home_dir.path())
+        .env("XDG_CONFIG_HOME", home_dir.path())
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .current_dir(repo_dir.path())
+        .output()?;
+
+    let output = common::render_module("git_user")
+        .use_config(toml::toml! {
+            [git_user]
+            disabled = false
+        })
+        .env("HOME", home_dir.path())
+        .env("XDG_CONFIG_HOME", home_dir.path())
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .arg("--path")
+        .arg(repo_dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("as {} ", Color::Red.bold().paint("no identity"));
+    assert_eq!(expected, actual);
+    home_dir.close()?;
+    repo_dir.close()
+}
@@ -9,8 +9,12 @@ mod dotnet;
 mod env_var;
 mod git_branch;
 mod git_commit;
+mod git_last_commit;
+mod git_metrics;
+mod git_remote;
 mod git_state;
 mod git_status;
+mod git_user;
 mod hg_branch;
 mod hostname;
 mod jobs;
@@ -19,6 +23,9 @@ mod modules;
 mod nix_shell;
 mod python;
 mod singularity;
+mod status;
 mod terraform;
 mod time;
+mod timings;
+mod title;
 mod username;
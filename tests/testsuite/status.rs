@@ -0,0 +1,164 @@
+use ansi_term::Color;
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+fn render_status(status: &str, config: toml::Value) -> io::Result<String> {
+    let output = common::render_module("status")
+        .use_config(config)
+        .arg(format!("--status={}", status))
+        .output()?;
+    Ok(String::from_utf8(output.stdout).unwrap())
+}
+
+#[test]
+fn disabled_by_default() -> io::Result<()> {
+    let output = common::render_module("status").arg("--status=1").output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = "";
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn hidden_on_success() -> io::Result<()> {
+    let actual = render_status(
+        "0",
+        toml::toml! {
+            [status]
+            disabled = false
+        },
+    )?;
+
+    let expected = "";
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn shown_on_success() -> io::Result<()> {
+    let actual = render_status(
+        "0",
+        toml::toml! {
+            [status]
+            disabled = false
+            show_on_success = true
+        },
+    )?;
+
+    let expected = format!("{} ", Color::Green.bold().paint("✔0"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn failure() -> io::Result<()> {
+    let actual = render_status(
+        "1",
+        toml::toml! {
+            [status]
+            disabled = false
+        },
+    )?;
+
+    let expected = format!("{} ", Color::Red.bold().paint("✖1"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn not_found_and_not_executable() -> io::Result<()> {
+    let config = toml::toml! {
+        [status]
+        disabled = false
+    };
+
+    let actual = render_status("127", config.clone())?;
+    let expected = format!("{} ", Color::Red.bold().paint("🔍127 not found"));
+    assert_eq!(expected, actual);
+
+    let actual = render_status("126", config)?;
+    let expected = format!("{} ", Color::Red.bold().paint("🚫126 not executable"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn signal() -> io::Result<()> {
+    let actual = render_status(
+        "130",
+        toml::toml! {
+            [status]
+            disabled = false
+            signal_symbol = { value = "SIG ", style = "bold yellow" }
+        },
+    )?;
+
+    let expected = format!("{} ", Color::Yellow.bold().paint("SIG 130 SIGINT"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn signal_without_meaning() -> io::Result<()> {
+    let actual = render_status(
+        "139",
+        toml::toml! {
+            [status]
+            disabled = false
+            show_meaning = false
+        },
+    )?;
+
+    let expected = format!("{} ", Color::Red.bold().paint("⚡139"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn pipestatus() -> io::Result<()> {
+    let output = common::render_module("status")
+        .use_config(toml::toml! {
+            [status]
+            disabled = false
+        })
+        .arg("--status=0")
+        .arg("--pipestatus=2 0")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("{} ", Color::Red.bold().paint("✖2|0"));
+    assert_eq!(expected, actual);
+
+    // A pipeline which succeeded is hidden like a single command
+    let output = common::render_module("status")
+        .use_config(toml::toml! {
+            [status]
+            disabled = false
+        })
+        .arg("--status=0")
+        .arg("--pipestatus=0 0")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!("", actual);
+    Ok(())
+}
+
+#[test]
+fn pipestatus_disabled() -> io::Result<()> {
+    let output = common::render_module("status")
+        .use_config(toml::toml! {
+            [status]
+            disabled = false
+            pipestatus = false
+        })
+        .arg("--status=1")
+        .arg("--pipestatus=0 1")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("{} ", Color::Red.bold().paint("✖1"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
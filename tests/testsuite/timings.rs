@@ -0,0 +1,74 @@
+use remove_dir_all::remove_dir_all;
+use std::io;
+use std::process::Command;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn lists_modules_slowest_first() -> io::Result<()> {
+    let output = common::render_timings()
+        .env("TIMINGS_VAR", "astronauts")
+        .use_config(toml::toml! {
+            prompt_order = ["env_var", "custom.slow", "line_break"]
+            [env_var]
+            variable = "TIMINGS_VAR"
+            prefix = ""
+            style = ""
+            [custom.slow]
+            command = "sleep 0.05; echo slow"
+            when = "true"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(actual.contains("Here are the timings of modules in your prompt (slowest first):"));
+    let rows: Vec<&str> = actual
+        .lines()
+        .filter(|line| line.contains("ms  -  "))
+        .collect();
+    assert_eq!(rows.len(), 3);
+    assert!(rows[0].starts_with(" custom.slow"));
+    assert!(rows
+        .iter()
+        .any(|row| row.starts_with(" env_var") && row.ends_with("-  astronauts")));
+    assert!(rows
+        .iter()
+        .any(|row| row.starts_with(" line_break") && row.ends_with("-  ")));
+
+    let durations: Vec<f64> = rows
+        .iter()
+        .map(|row| {
+            let duration = row.split("ms  -  ").next().unwrap();
+            duration.split_whitespace().last().unwrap().parse().unwrap()
+        })
+        .collect();
+    assert!(durations.windows(2).all(|pair| pair[0] >= pair[1]));
+    assert!(actual.contains("since modules are computed in parallel)"));
+    Ok(())
+}
+
+#[test]
+fn records_work_on_other_threads() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    // The status is read from `git status` on its own thread, within the timeout
+    Command::new("git")
+        .args(&["config", "core.untrackedCache", "true"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+
+    let output = common::render_timings()
+        .use_config(toml::toml! {
+            prompt_order = ["git_status"]
+            [git_status]
+            status_timeout = 10000
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(actual.contains("triggered repo_status"));
+    assert!(actual.contains("ran `git -C"));
+    remove_dir_all(repo_dir)
+}
@@ -0,0 +1,96 @@
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn disabled_by_default() -> io::Result<()> {
+    let output = common::render_prompt()
+        .env("STARSHIP_SHELL", "bash")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(!actual.contains("\x1b]0;"));
+
+    let output = common::render_title().arg("--command=ls").output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!("", actual);
+    Ok(())
+}
+
+#[test]
+fn prompt_sets_title() -> io::Result<()> {
+    let output = common::render_prompt()
+        .env("STARSHIP_SHELL", "bash")
+        .env("TITLE_VAR", "astronauts")
+        .use_config(toml::toml! {
+            [title]
+            disabled = false
+            format = "starship - $env_var"
+            [env_var]
+            variable = "TITLE_VAR"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(actual.starts_with("\\[\x1b]0;starship - astronauts\x07\\]"));
+    Ok(())
+}
+
+#[test]
+fn prompt_title_with_module_outside_prompt() -> io::Result<()> {
+    let output = common::render_prompt()
+        .env("STARSHIP_SHELL", "bash")
+        .env("TITLE_VAR", "astronauts")
+        .use_config(toml::toml! {
+            prompt_order = ["character"]
+            [title]
+            disabled = false
+            format = "starship - $env_var"
+            [env_var]
+            variable = "TITLE_VAR"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(actual.starts_with("\\[\x1b]0;starship - astronauts\x07\\]"));
+    assert_eq!(1, actual.matches("astronauts").count());
+    Ok(())
+}
+
+#[test]
+fn prompt_title_in_zsh() -> io::Result<()> {
+    let output = common::render_prompt()
+        .env("STARSHIP_SHELL", "zsh")
+        .use_config(toml::toml! {
+            [title]
+            disabled = false
+            format = "starship"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(actual.starts_with("%{\x1b]0;starship\x07%}"));
+    Ok(())
+}
+
+#[test]
+fn preexec_title_shows_command() -> io::Result<()> {
+    let output = common::render_title()
+        .arg("--command=cargo build")
+        .use_config(toml::toml! {
+            [title]
+            disabled = false
+            preexec_format = "running $command"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!("\x1b]0;running cargo build\x07", actual);
+
+    let output = common::render_title()
+        .arg("--plain")
+        .arg("--command=cargo build")
+        .use_config(toml::toml! {
+            [title]
+            disabled = false
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!("cargo build", actual);
+    Ok(())
+}